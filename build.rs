@@ -0,0 +1,371 @@
+//! Generates static Rust data from the CHIP-8 Database when the `static-data` feature is enabled,
+//! so lookups don't need to parse any JSON at runtime.
+
+fn main() {
+    #[cfg(feature = "static-data")]
+    static_data::generate();
+}
+
+#[cfg(feature = "static-data")]
+mod static_data {
+    use serde_json::Value;
+    use std::{env, fmt::Write, fs, path::Path};
+
+    const PROGRAMS: &str = "chip-8-database/database/programs.json";
+    const HASHES: &str = "chip-8-database/database/sha1-hashes.json";
+
+    pub fn generate() {
+        println!("cargo:rerun-if-changed={PROGRAMS}");
+        println!("cargo:rerun-if-changed={HASHES}");
+
+        let programs = read(PROGRAMS);
+        let programs = programs.as_array().expect("programs.json should be a list");
+        let hashes = read(HASHES);
+        let hashes = hashes
+            .as_object()
+            .expect("sha1-hashes.json should be an object");
+
+        let mut out = String::new();
+        let mut map = phf_codegen::Map::new();
+
+        writeln!(
+            out,
+            "/// Every program in `programs.json`, in the same order."
+        )
+        .unwrap();
+        writeln!(out, "pub static PROGRAMS: &[StaticProgram] = &[").unwrap();
+
+        for program in programs {
+            writeln!(out, "{},", program_literal(program)).unwrap();
+        }
+
+        writeln!(out, "];\n").unwrap();
+
+        for (hash, index) in hashes {
+            let index = index.as_u64().expect("program indices should be integers") as usize;
+            let roms = programs[index]["roms"]
+                .as_object()
+                .expect("every program should have roms");
+            let rom = roms
+                .keys()
+                .position(|key| key == hash)
+                .unwrap_or_else(|| panic!("program {index} should contain ROM {hash}"));
+
+            map.entry(bytes(hash), &format!("({index}, {rom})"));
+        }
+
+        writeln!(
+            out,
+            "/// A perfect hash map of every hash in `sha1-hashes.json` to the index of its \
+             program and ROM."
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "static HASHES: phf::Map<[u8; 20], (usize, usize)> = {};",
+            map.build()
+        )
+        .unwrap();
+
+        variant_tables(&mut out);
+
+        let path = Path::new(&env::var("OUT_DIR").unwrap()).join("static_data.rs");
+        fs::write(path, out).expect("failed to write the generated static data");
+    }
+
+    fn read(path: &str) -> Value {
+        let json =
+            fs::read_to_string(path).unwrap_or_else(|e| panic!("failed to read {path}: {e}"));
+
+        serde_json::from_str(&json).unwrap_or_else(|e| panic!("failed to parse {path}: {e}"))
+    }
+
+    fn program_literal(program: &Value) -> String {
+        let roms = program["roms"]
+            .as_object()
+            .expect("every program should have roms")
+            .iter()
+            .map(|(hash, rom)| format!("(RomHash({:?}), {})", bytes(hash), rom_literal(rom)))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        format!(
+            "StaticProgram {{ title: {:?}, description: {}, release: {}, origin: {}, \
+             copyright: {}, license: {}, authors: {}, images: {}, urls: {}, roms: &[{roms}] }}",
+            program["title"]
+                .as_str()
+                .expect("every program should have a title"),
+            optional_str(&program["description"]),
+            optional_str(&program["release"]),
+            optional(&program["origin"], |origin| format!(
+                "StaticOrigin {{ origin_type: {}, reference: {} }}",
+                optional(&origin["type"], |kind| variant("OriginType", as_str(kind))),
+                optional_str(&origin["reference"]),
+            )),
+            optional_str(&program["copyright"]),
+            optional_str(&program["license"]),
+            optional_strs(&program["authors"]),
+            optional_strs(&program["images"]),
+            optional_strs(&program["urls"]),
+        )
+    }
+
+    fn rom_literal(rom: &Value) -> String {
+        format!(
+            "StaticRom {{ file_name: {}, embedded_title: {}, description: {}, release: {}, \
+             platforms: &[{}], quirky_platforms: {}, authors: {}, images: {}, urls: {}, \
+             tickrate: {}, start_address: {}, screen_rotation: {}, keys: {}, \
+             touch_input_mode: {}, font_style: {}, colors: {} }}",
+            optional_str(&rom["file"]),
+            optional_str(&rom["embeddedTitle"]),
+            optional_str(&rom["description"]),
+            optional_str(&rom["release"]),
+            rom["platforms"]
+                .as_array()
+                .expect("every ROM should have platforms")
+                .iter()
+                .map(|platform| variant("Platform", as_str(platform)))
+                .collect::<Vec<_>>()
+                .join(", "),
+            optional(&rom["quirkyPlatforms"], |platforms| {
+                let platforms = entries(platforms)
+                    .map(|(platform, quirks)| {
+                        let quirks = entries(quirks)
+                            .map(|(quirk, enabled)| {
+                                format!(".with({}, {enabled})", variant("Quirk", quirk))
+                            })
+                            .collect::<String>();
+
+                        format!(
+                            "({}, QuirkSet::new(){quirks})",
+                            variant("Platform", platform)
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                format!("&[{platforms}]")
+            }),
+            optional_strs(&rom["authors"]),
+            optional_strs(&rom["images"]),
+            optional_strs(&rom["urls"]),
+            optional(&rom["tickrate"], |tickrate| tickrate.to_string()),
+            optional(&rom["startAddress"], |address| address.to_string()),
+            optional(&rom["screenRotation"], rotation),
+            optional(&rom["keys"], |keys| {
+                let keys = entries(keys)
+                    .map(|(key, value)| format!("({}, {value})", variant("Keymap", key)))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                format!("&[{keys}]")
+            }),
+            optional(&rom["touchInputMode"], |mode| variant(
+                "TouchInputMode",
+                as_str(mode)
+            )),
+            optional(&rom["fontStyle"], |style| variant(
+                "FontStyle",
+                as_str(style)
+            )),
+            optional(&rom["colors"], |colors| format!(
+                "StaticColors {{ pixels: {}, buzzer: {}, silence: {} }}",
+                optional_strs(&colors["pixels"]),
+                optional_str(&colors["buzzer"]),
+                optional_str(&colors["silence"]),
+            )),
+        )
+    }
+
+    /// Decode a hexadecimal SHA-1 hash into its raw bytes.
+    fn bytes(hash: &str) -> [u8; 20] {
+        let mut bytes = [0u8; 20];
+
+        assert_eq!(40, hash.len(), "{hash:?} should be a SHA-1 hash");
+
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hash[i * 2..i * 2 + 2], 16)
+                .unwrap_or_else(|_| panic!("{hash:?} should be a SHA-1 hash"));
+        }
+
+        bytes
+    }
+
+    fn entries(value: &Value) -> impl Iterator<Item = (&String, &Value)> {
+        value.as_object().expect("expected an object").iter()
+    }
+
+    fn optional(value: &Value, literal: impl FnOnce(&Value) -> String) -> String {
+        match value {
+            Value::Null => "None".to_owned(),
+            value => format!("Some({})", literal(value)),
+        }
+    }
+
+    fn as_str(value: &Value) -> &str {
+        value.as_str().expect("expected a string")
+    }
+
+    fn optional_str(value: &Value) -> String {
+        optional(value, |value| format!("{:?}", as_str(value)))
+    }
+
+    fn optional_strs(value: &Value) -> String {
+        optional(value, |value| {
+            let strs = value
+                .as_array()
+                .expect("expected a list of strings")
+                .iter()
+                .map(|value| format!("{:?}", as_str(value)))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            format!("&[{strs}]")
+        })
+    }
+
+    /// Every screen rotation in the JSON schema, in degrees, and its matching variant.
+    const ROTATIONS: &[(u64, &str)] = &[
+        (0, "Landscape"),
+        (90, "Portrait"),
+        (180, "LandscapeFlipped"),
+        (270, "PortraitFlipped"),
+    ];
+
+    fn rotation(degrees: &Value) -> String {
+        let name = ROTATIONS
+            .iter()
+            .find(|(value, _)| degrees.as_u64() == Some(*value))
+            .map(|(_, name)| name)
+            .unwrap_or_else(|| panic!("unknown screen rotation {degrees}"));
+
+        format!("ScreenRotation::{name}")
+    }
+
+    /// Every string in the JSON schema and its matching enum variant, by enum. This mirrors the
+    /// serde attributes on each enum in the library, which the generated `VARIANTS_*` tables are
+    /// tested against.
+    const VARIANTS: &[(&str, &[(&str, &str)])] = &[
+        (
+            "Platform",
+            &[
+                ("originalChip8", "OriginalChip8"),
+                ("hybridVIP", "HybridVIP"),
+                ("modernChip8", "ModernChip8"),
+                ("chip8x", "Chip8X"),
+                ("chip48", "Chip48"),
+                ("superchip1", "Superchip1"),
+                ("superchip", "Superchip"),
+                ("megachip8", "MegaChip8"),
+                ("xochip", "XOChip"),
+            ],
+        ),
+        (
+            "Quirk",
+            &[
+                ("shift", "Shift"),
+                ("memoryIncrementByX", "MemoryIncrementByX"),
+                ("memoryLeaveIUnchanged", "MemoryLeaveIUnchanged"),
+                ("wrap", "Wrap"),
+                ("jump", "Jump"),
+                ("vblank", "VBlank"),
+                ("logic", "Logic"),
+            ],
+        ),
+        (
+            "Keymap",
+            &[
+                ("up", "P1Up"),
+                ("down", "P1Down"),
+                ("left", "P1Left"),
+                ("right", "P1Right"),
+                ("a", "P1A"),
+                ("b", "P1B"),
+                ("player2Up", "P2Up"),
+                ("player2Down", "P2Down"),
+                ("player2Left", "P2Left"),
+                ("player2Right", "P2Right"),
+                ("player2A", "P2A"),
+                ("player2B", "P2B"),
+            ],
+        ),
+        (
+            "OriginType",
+            &[
+                ("gamejam", "GameJam"),
+                ("event", "Event"),
+                ("magazine", "Magazine"),
+                ("manual", "Manual"),
+            ],
+        ),
+        (
+            "TouchInputMode",
+            &[
+                ("none", "None"),
+                ("swipe", "Swipe"),
+                ("seg16", "Seg16"),
+                ("seg16fill", "Seg16Fill"),
+                ("gamepad", "Gamepad"),
+                ("vip", "VIP"),
+            ],
+        ),
+        (
+            "FontStyle",
+            &[
+                ("vip", "VIP"),
+                ("octo", "Octo"),
+                ("schip", "SCHIP"),
+                ("dream6800", "Dream6800"),
+                ("eti660", "ETI660"),
+                ("fish", "Fish"),
+                ("akouz1", "Akouz1"),
+            ],
+        ),
+    ];
+
+    /// Map a string from the JSON schema to the matching enum variant.
+    fn variant(kind: &str, value: &str) -> String {
+        let name = VARIANTS
+            .iter()
+            .filter(|(k, _)| *k == kind)
+            .flat_map(|(_, variants)| variants.iter())
+            .find(|(json, _)| *json == value)
+            .map(|(_, name)| name)
+            .unwrap_or_else(|| panic!("unknown {kind} {value:?}"));
+
+        format!("{kind}::{name}")
+    }
+
+    /// Write the `VARIANTS_*` and `ROTATIONS` tables, so the tests can check every mapping above
+    /// against the serde names of the library's enums.
+    fn variant_tables(out: &mut String) {
+        for (kind, variants) in VARIANTS {
+            let variants = variants
+                .iter()
+                .map(|(json, name)| format!("({json:?}, {kind}::{name})"))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            writeln!(out, "#[cfg(test)]").unwrap();
+            writeln!(
+                out,
+                "pub(crate) static VARIANTS_{}: &[(&str, {kind})] = &[{variants}];",
+                kind.to_uppercase()
+            )
+            .unwrap();
+        }
+
+        let rotations = ROTATIONS
+            .iter()
+            .map(|(degrees, name)| format!("({degrees}, ScreenRotation::{name})"))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        writeln!(out, "#[cfg(test)]").unwrap();
+        writeln!(
+            out,
+            "pub(crate) static ROTATIONS: &[(u64, ScreenRotation)] = &[{rotations}];"
+        )
+        .unwrap();
+    }
+}
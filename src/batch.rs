@@ -0,0 +1,197 @@
+//! Definitions related to identifying many ROM files at once, such as a user's ROM folder.
+//!
+//! With the `rayon` feature, files are hashed in parallel.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::{Database, Metadata};
+
+/// The file extensions [Database::identify_dir] treats as ROM files, compared case-insensitively.
+pub const ROM_EXTENSIONS: &[&str] = &["ch8", "c8", "c8x", "hc8", "sc8", "mc8", "xo8"];
+
+/// The result of identifying a batch of ROM files.
+#[derive(Debug, Default)]
+pub struct BatchReport {
+    /// The metadata for every file that could be read, in the order the files were given.
+    pub results: Vec<(PathBuf, Metadata)>,
+
+    /// An overview of how many files were recognized, and which ones need attention.
+    pub summary: BatchSummary,
+}
+
+/// An overview of a [BatchReport].
+#[derive(Debug, Default)]
+pub struct BatchSummary {
+    /// The number of files whose hash was found in the database.
+    pub matched: usize,
+
+    /// The number of files whose hash is not in the database.
+    pub unknown: usize,
+
+    /// Recognized files with a different name than the one in the database.
+    pub renamed: Vec<Renamed>,
+
+    /// Files that could not be read, along with the reason.
+    pub failed: Vec<(PathBuf, io::Error)>,
+}
+
+/// A recognized ROM file whose name differs from [Rom.file_name](crate::rom::Rom::file_name).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Renamed {
+    /// The path of the file as it was found.
+    pub path: PathBuf,
+
+    /// The file name listed in the database for this ROM.
+    pub expected: String,
+}
+
+impl Database {
+    /// Identify every ROM file directly inside a directory, going by [ROM_EXTENSIONS].
+    ///
+    /// Subdirectories are not searched, but symbolic links to files are followed. Files are
+    /// identified in order of their paths, and an error is only returned if the directory itself
+    /// can't be read. Entries that can't be read are listed in
+    /// [BatchSummary.failed](BatchSummary::failed) instead.
+    pub fn identify_dir(&self, path: impl AsRef<Path>) -> io::Result<BatchReport> {
+        let path = path.as_ref();
+        let (paths, failed) = rom_paths(path)?;
+
+        let mut report = self.identify_paths(paths);
+        report
+            .summary
+            .failed
+            .extend(failed.into_iter().map(|error| (path.to_owned(), error)));
+
+        Ok(report)
+    }
+
+    /// Identify every file in `paths`, regardless of its extension.
+    pub fn identify_paths(&self, paths: impl IntoIterator<Item = PathBuf>) -> BatchReport {
+        let paths: Vec<PathBuf> = paths.into_iter().collect();
+
+        #[cfg(feature = "rayon")]
+        let identified: Vec<_> = paths
+            .into_par_iter()
+            .map(|path| {
+                let metadata = self.get_metadata_from_path(&path);
+                (path, metadata)
+            })
+            .collect();
+
+        #[cfg(not(feature = "rayon"))]
+        let identified: Vec<_> = paths
+            .into_iter()
+            .map(|path| {
+                let metadata = self.get_metadata_from_path(&path);
+                (path, metadata)
+            })
+            .collect();
+
+        let mut report = BatchReport::default();
+
+        for (path, metadata) in identified {
+            match metadata {
+                Ok(metadata) => {
+                    report.summary.record(&path, &metadata);
+                    report.results.push((path, metadata));
+                }
+                Err(error) => report.summary.failed.push((path, error)),
+            }
+        }
+
+        report
+    }
+}
+
+impl BatchSummary {
+    fn record(&mut self, path: &Path, metadata: &Metadata) {
+        let Some(rom) = &metadata.rom else {
+            self.unknown += 1;
+            return;
+        };
+
+        self.matched += 1;
+
+        if let Some(expected) = &rom.file_name {
+            if path.file_name().and_then(|name| name.to_str()) != Some(expected.as_str()) {
+                self.renamed.push(Renamed {
+                    path: path.to_owned(),
+                    expected: expected.clone(),
+                });
+            }
+        }
+    }
+}
+
+/// Every ROM file directly inside `dir`, sorted by path, going by [ROM_EXTENSIONS].
+///
+/// Symbolic links are followed. ROM files whose metadata can't be read are still listed, so the
+/// error is reported along with their path when they are opened. Entries of the directory that
+/// can't be read at all are returned as errors next to the paths, since they don't have a path.
+fn rom_paths(dir: &Path) -> io::Result<(Vec<PathBuf>, Vec<io::Error>)> {
+    let mut paths = Vec::new();
+    let mut failed = Vec::new();
+
+    for entry in fs::read_dir(dir)? {
+        let path = match entry {
+            Ok(entry) => entry.path(),
+            Err(error) => {
+                failed.push(error);
+                continue;
+            }
+        };
+
+        let is_file = match fs::metadata(&path) {
+            Ok(metadata) => metadata.is_file(),
+            Err(_) => true,
+        };
+
+        if is_file && is_rom(&path) {
+            paths.push(path);
+        }
+    }
+
+    paths.sort();
+
+    Ok((paths, failed))
+}
+
+/// Pass the contents of every ROM file directly inside `dir` to `read`, going by [ROM_EXTENSIONS].
+///
+/// An error is only returned if the directory itself can't be read. Files and entries that can't
+/// be read are skipped and returned along with their path, or `dir` for entries without one, in
+/// the same way as [BatchSummary.failed](BatchSummary::failed).
+pub(crate) fn read_roms(
+    dir: &Path,
+    mut read: impl FnMut(&[u8]),
+) -> io::Result<Vec<(PathBuf, io::Error)>> {
+    let (paths, unreadable) = rom_paths(dir)?;
+    let mut failed = Vec::new();
+
+    for path in paths {
+        match fs::read(&path) {
+            Ok(rom) => read(&rom),
+            Err(error) => failed.push((path, error)),
+        }
+    }
+
+    failed.extend(unreadable.into_iter().map(|error| (dir.to_owned(), error)));
+
+    Ok(failed)
+}
+
+fn is_rom(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            ROM_EXTENSIONS
+                .iter()
+                .any(|rom| rom.eq_ignore_ascii_case(extension))
+        })
+}
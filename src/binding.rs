@@ -0,0 +1,248 @@
+//! Definitions related to binding the keyboard and gamepads of the host to the CHIP-8 keypad.
+//!
+//! A [Layout] lists which host inputs control the raw hexadecimal keypad and which control the
+//! named buttons of [Keymap]. Resolving it against the [keys](crate::rom::Rom::keys) of a ROM
+//! gives the CHIP-8 key for every host input.
+//!
+//! ```rust
+//! # use chip8_db::{binding::{HostInput, Key, Layout}, input::Keymap};
+//! # use std::collections::HashMap;
+//! #
+//! let keys = HashMap::from([(Keymap::P1Up, 0x5), (Keymap::P1A, 0x6)]);
+//! let bindings = Layout::default().resolve(Some(&keys));
+//!
+//! assert_eq!(Some(0x5), bindings.get(HostInput::Key(Key::Up)));
+//! assert_eq!(Some(0x5), bindings.get(HostInput::Key(Key::W)));
+//! ```
+
+use std::collections::HashMap;
+
+use crate::input::Keymap;
+
+/// A key on the host keyboard, named by its position on a QWERTY keyboard.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Key {
+    Digit1,
+    Digit2,
+    Digit3,
+    Digit4,
+    Q,
+    W,
+    E,
+    R,
+    A,
+    S,
+    D,
+    F,
+    Z,
+    X,
+    C,
+    V,
+    Up,
+    Down,
+    Left,
+    Right,
+    Space,
+    Enter,
+}
+
+/// A button on a gamepad with a D-Pad and face buttons.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum GamepadButton {
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+
+    /// The bottom face button, such as A on an Xbox controller or B on a Nintendo controller.
+    South,
+
+    /// The right face button, such as B on an Xbox controller or A on a Nintendo controller.
+    East,
+}
+
+/// A single input on the host.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum HostInput {
+    /// A key on the keyboard.
+    Key(Key),
+
+    /// A button on a gamepad, where `player` counts from 1.
+    Gamepad {
+        /// The player holding the gamepad, counting from 1.
+        player: u8,
+
+        /// The button on the gamepad.
+        button: GamepadButton,
+    },
+}
+
+/// The named buttons of each player, in the order of the D-Pad and then the face buttons.
+const PLAYER1: [Keymap; 6] = [
+    Keymap::P1Up,
+    Keymap::P1Down,
+    Keymap::P1Left,
+    Keymap::P1Right,
+    Keymap::P1A,
+    Keymap::P1B,
+];
+const PLAYER2: [Keymap; 6] = [
+    Keymap::P2Up,
+    Keymap::P2Down,
+    Keymap::P2Left,
+    Keymap::P2Right,
+    Keymap::P2A,
+    Keymap::P2B,
+];
+
+/// The raw hexadecimal key that a named button falls back to when a ROM doesn't map it, following
+/// the cross of `2`, `4`, `6` and `8` around `5` that most CHIP-8 games use for their controls.
+///
+/// Only the buttons of player 2 fall back to these keys in [Layout::resolve], since player 1 shares
+/// the raw hexadecimal keypad of [Layout::hex_pad] already.
+pub const fn hex_pad_key(control: Keymap) -> u8 {
+    match control {
+        Keymap::P1Up | Keymap::P2Up => 0x2,
+        Keymap::P1Down | Keymap::P2Down => 0x8,
+        Keymap::P1Left | Keymap::P2Left => 0x4,
+        Keymap::P1Right | Keymap::P2Right => 0x6,
+        Keymap::P1A | Keymap::P2A => 0x5,
+        Keymap::P1B | Keymap::P2B => 0x0,
+    }
+}
+
+/// Which host inputs control the CHIP-8 keypad.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Layout {
+    /// The host input for each key of the hexadecimal keypad, by CHIP-8 key value. These are bound
+    /// for every ROM.
+    pub hex_pad: [HostInput; 16],
+
+    /// The host inputs for the named buttons, bound if the ROM maps that button to a CHIP-8 key.
+    /// Earlier entries take precedence when the same input is listed more than once.
+    pub controls: Vec<(HostInput, Keymap)>,
+}
+
+impl Default for Layout {
+    /// The hexadecimal keypad on the left side of a QWERTY keyboard as used by most interpreters,
+    /// with player 1 on the arrow keys, space, enter and the first gamepad, and player 2 on the
+    /// second gamepad.
+    ///
+    /// ```text
+    /// 1 2 3 C        1 2 3 4
+    /// 4 5 6 D   ->   Q W E R
+    /// 7 8 9 E        A S D F
+    /// A 0 B F        Z X C V
+    /// ```
+    fn default() -> Self {
+        use GamepadButton::*;
+        use Key::*;
+
+        let keys = [Up, Down, Left, Right, Space, Enter];
+        let mut controls: Vec<_> = keys.map(HostInput::Key).into_iter().zip(PLAYER1).collect();
+
+        for (player, controls_of_player) in [(1, PLAYER1), (2, PLAYER2)] {
+            let buttons = [DPadUp, DPadDown, DPadLeft, DPadRight, South, East];
+
+            for (button, control) in buttons.into_iter().zip(controls_of_player) {
+                controls.push((HostInput::Gamepad { player, button }, control));
+            }
+        }
+
+        Layout {
+            hex_pad: [
+                X, Digit1, Digit2, Digit3, Q, W, E, A, S, D, Z, C, Digit4, R, F, V,
+            ]
+            .map(HostInput::Key),
+            controls,
+        }
+    }
+}
+
+/// Two bindings that were resolved for the same host input, of which only the first was kept.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Conflict {
+    /// The host input that was listed more than once.
+    pub input: HostInput,
+
+    /// The CHIP-8 key the input stays bound to.
+    pub kept: u8,
+
+    /// The CHIP-8 key the input would also have been bound to.
+    pub dropped: u8,
+}
+
+/// The CHIP-8 key for every host input, resolved by [Layout::resolve].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Bindings {
+    /// The CHIP-8 key each bound host input presses. Keys of `0x10` and above belong to the
+    /// additional keypads of ROMs that use more than one, and are kept as they are.
+    pub keys: HashMap<HostInput, u8>,
+
+    /// Every host input that was listed for different CHIP-8 keys, in the order they were found.
+    pub conflicts: Vec<Conflict>,
+
+    /// The named buttons in the layout that the ROM doesn't map. Those of player 2 are bound to
+    /// their [hex_pad_key] instead, while those of player 1 are left unbound.
+    pub unmapped: Vec<Keymap>,
+}
+
+impl Bindings {
+    /// The CHIP-8 key pressed by a host input, if it is bound.
+    pub fn get(&self, input: HostInput) -> Option<u8> {
+        self.keys.get(&input).copied()
+    }
+
+    fn bind(&mut self, input: HostInput, key: u8) {
+        match self.keys.get(&input) {
+            Some(&kept) if kept != key => self.conflicts.push(Conflict {
+                input,
+                kept,
+                dropped: key,
+            }),
+            Some(_) => {}
+            None => {
+                self.keys.insert(input, key);
+            }
+        }
+    }
+}
+
+impl Layout {
+    /// Bind the inputs of this layout to the CHIP-8 keys of a ROM, given its
+    /// [keys](crate::rom::Rom::keys).
+    ///
+    /// Named buttons are bound first, in the order of [Layout::controls], and the raw hexadecimal
+    /// keypad after that. When an input would be bound to more than one CHIP-8 key, the first
+    /// binding wins and the rest are listed in [Bindings::conflicts].
+    ///
+    /// Many ROMs don't map the controls of a second player, so buttons of player 2 that the ROM
+    /// doesn't map fall back to their [hex_pad_key]. Buttons of player 1 that the ROM doesn't map
+    /// are left unbound, since the raw hexadecimal keypad is always available to them.
+    pub fn resolve(&self, keys: Option<&HashMap<Keymap, u8>>) -> Bindings {
+        let mut bindings = Bindings::default();
+
+        for &(input, control) in &self.controls {
+            if let Some(&key) = keys.and_then(|keys| keys.get(&control)) {
+                bindings.bind(input, key);
+                continue;
+            }
+
+            if !bindings.unmapped.contains(&control) {
+                bindings.unmapped.push(control);
+            }
+
+            if control.is_player2() {
+                bindings.bind(input, hex_pad_key(control));
+            }
+        }
+
+        for (key, &input) in (0..).zip(&self.hex_pad) {
+            bindings.bind(input, key);
+        }
+
+        bindings
+    }
+}
@@ -0,0 +1,243 @@
+//! Definitions related to the hardware and instruction set of each CHIP-8 platform.
+//!
+//! Unlike [PlatformDetails](crate::platform::PlatformDetails), these are built into the library and
+//! don't need the `extra-data` feature.
+//!
+//! ```rust
+//! # use chip8_db::{capability::Extension, platform::Platform};
+//! #
+//! let capabilities = Platform::XOChip.capabilities();
+//! let memory = vec![0u8; capabilities.memory_size];
+//!
+//! assert_eq!(65536, memory.len());
+//! assert!(capabilities.supports(Extension::LongI));
+//! ```
+
+use crate::platform::{Platform, Resolution};
+
+/// How a platform produces sound.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum AudioModel {
+    /// A single tone that plays while the sound timer is non-zero.
+    Buzzer,
+
+    /// A 1-bit pattern of 128 samples that plays while the sound timer is non-zero, at a pitch set
+    /// by `FX3A`, as introduced by XO-CHIP.
+    Pattern,
+
+    /// 8-bit digitized samples, as introduced by MEGA-CHIP. The buzzer is still available as well.
+    Samples,
+}
+
+/// A group of instructions added to the original CHIP-8 instruction set.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Extension {
+    /// The colour and I/O port instructions of CHIP-8X: `02A0`, `5XY1`, `BXY0`, `BXYN`, `EXF2`,
+    /// `EXF5`, `FXF8` and `FXFB`.
+    Chip8X,
+
+    /// Switching between low and high resolution with `00FE` and `00FF`, from SUPER-CHIP.
+    Hires,
+
+    /// Exiting the interpreter with `00FD`, from SUPER-CHIP.
+    Exit,
+
+    /// Drawing 16x16 sprites with `DXY0`, from SUPER-CHIP.
+    LargeSprites,
+
+    /// Pointing I at the large font with `FX30`, from SUPER-CHIP.
+    LargeFont,
+
+    /// Saving and loading the persistent flag registers with `FX75` and `FX85`, from SUPER-CHIP.
+    FlagRegisters,
+
+    /// Scrolling down with `00CN`, right with `00FB` and left with `00FC`, from SUPER-CHIP 1.1.
+    Scroll,
+
+    /// Scrolling up with `00DN`, from XO-CHIP.
+    ScrollUp,
+
+    /// Saving and loading a range of registers with `5XY2` and `5XY3`, from XO-CHIP.
+    RegisterRange,
+
+    /// Loading a 16-bit address into I with `F000 NNNN`, from XO-CHIP.
+    LongI,
+
+    /// Selecting the bitplanes to draw to with `FN01`, from XO-CHIP.
+    Planes,
+
+    /// Loading the audio pattern with `F002` and setting its pitch with `FX3A`, from XO-CHIP.
+    AudioPattern,
+
+    /// The instructions of MEGA-CHIP mode, such as `0010`, `0011`, `01NN` and `02NN` through
+    /// `08NN`, for 24-bit addresses, colour sprites, palettes and digitized sound.
+    MegaChip,
+}
+
+/// What the hardware and interpreter of a platform offer to programs.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Capabilities {
+    /// The size of the addressable memory in bytes, including the interpreter area below `0x200`.
+    pub memory_size: usize,
+
+    /// Every display resolution the platform supports, smallest first.
+    pub display_modes: &'static [Resolution],
+
+    /// The number of bits per pixel of the display.
+    pub bitplanes: u8,
+
+    /// How the platform produces sound.
+    pub audio: AudioModel,
+
+    /// The extensions to the original CHIP-8 instruction set that the platform supports.
+    pub extensions: &'static [Extension],
+
+    /// The number of return addresses the stack can hold.
+    pub stack_depth: usize,
+}
+
+impl Capabilities {
+    /// Whether the platform supports an extension to the instruction set.
+    pub fn supports(&self, extension: Extension) -> bool {
+        self.extensions.contains(&extension)
+    }
+
+    /// The smallest display resolution, which programs start out in. Returns `None` if
+    /// [display_modes](Capabilities::display_modes) is empty, which is never the case for the
+    /// capabilities of a [Platform].
+    pub const fn lowres(&self) -> Option<Resolution> {
+        match self.display_modes {
+            [lowres, ..] => Some(*lowres),
+            [] => None,
+        }
+    }
+
+    /// The high resolution mode, which is the second smallest display resolution. Returns `None`
+    /// for platforms with only a single resolution.
+    ///
+    /// Platforms with more than two resolutions, such as [MEGA-CHIP](Platform::MegaChip8), have
+    /// additional modes beyond this that are only listed in
+    /// [display_modes](Capabilities::display_modes).
+    pub const fn hires(&self) -> Option<Resolution> {
+        match self.display_modes {
+            [_, hires, ..] => Some(*hires),
+            _ => None,
+        }
+    }
+}
+
+const LORES: &[Resolution] = &[Resolution::new(64, 32)];
+const HIRES: &[Resolution] = &[Resolution::new(64, 32), Resolution::new(128, 64)];
+const MEGACHIP_MODES: &[Resolution] = &[
+    Resolution::new(64, 32),
+    Resolution::new(128, 64),
+    Resolution::new(256, 192),
+];
+
+const SUPERCHIP1: [Extension; 5] = [
+    Extension::Hires,
+    Extension::Exit,
+    Extension::LargeSprites,
+    Extension::LargeFont,
+    Extension::FlagRegisters,
+];
+const SUPERCHIP: [Extension; 6] = concat(&SUPERCHIP1, &[Extension::Scroll]);
+const MEGACHIP: [Extension; 7] = concat(&SUPERCHIP, &[Extension::MegaChip]);
+const XOCHIP: [Extension; 11] = concat(
+    &SUPERCHIP,
+    &[
+        Extension::ScrollUp,
+        Extension::RegisterRange,
+        Extension::LongI,
+        Extension::Planes,
+        Extension::AudioPattern,
+    ],
+);
+
+/// Append the extensions of `b` to those of `a`, so each platform only lists what it adds.
+const fn concat<const N: usize>(a: &[Extension], b: &[Extension]) -> [Extension; N] {
+    assert!(a.len() + b.len() == N, "the lists should fill the array");
+
+    let mut extensions = [Extension::Hires; N];
+    let mut i = 0;
+
+    while i < N {
+        extensions[i] = if i < a.len() { a[i] } else { b[i - a.len()] };
+        i += 1;
+    }
+
+    extensions
+}
+
+const VIP: Capabilities = Capabilities {
+    memory_size: 4096,
+    display_modes: LORES,
+    bitplanes: 1,
+    audio: AudioModel::Buzzer,
+    extensions: &[],
+    stack_depth: 12,
+};
+
+impl Platform {
+    /// The capabilities of this platform, as a single source of truth to configure an emulator.
+    ///
+    /// These describe the platform as commonly emulated. The original COSMAC VIP interpreters have
+    /// room for 12 return addresses, while later platforms have 16.
+    pub const fn capabilities(self) -> Capabilities {
+        match self {
+            Platform::OriginalChip8 | Platform::HybridVIP => VIP,
+            Platform::Chip8X => Capabilities {
+                extensions: &[Extension::Chip8X],
+                ..VIP
+            },
+            Platform::ModernChip8 | Platform::Chip48 => Capabilities {
+                stack_depth: 16,
+                ..VIP
+            },
+            Platform::Superchip1 => Capabilities {
+                display_modes: HIRES,
+                extensions: &SUPERCHIP1,
+                stack_depth: 16,
+                ..VIP
+            },
+            Platform::Superchip => Capabilities {
+                display_modes: HIRES,
+                extensions: &SUPERCHIP,
+                stack_depth: 16,
+                ..VIP
+            },
+            Platform::MegaChip8 => Capabilities {
+                memory_size: 0x1000000,
+                display_modes: MEGACHIP_MODES,
+                bitplanes: 8,
+                audio: AudioModel::Samples,
+                extensions: &MEGACHIP,
+                stack_depth: 16,
+            },
+            Platform::XOChip => Capabilities {
+                memory_size: 0x10000,
+                display_modes: HIRES,
+                bitplanes: 2,
+                audio: AudioModel::Pattern,
+                extensions: &XOCHIP,
+                stack_depth: 16,
+            },
+        }
+    }
+
+    /// The smallest display resolution of this platform, which programs start out in. See
+    /// [Capabilities::lowres].
+    pub const fn lowres(self) -> Resolution {
+        match self.capabilities().lowres() {
+            Some(lowres) => lowres,
+            None => panic!("every platform should have a display mode"),
+        }
+    }
+
+    /// The high resolution mode of this platform, if it has one. See [Capabilities::hires].
+    pub const fn hires(self) -> Option<Resolution> {
+        self.capabilities().hires()
+    }
+}
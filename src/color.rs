@@ -1,6 +1,11 @@
 //! Definitions related to colors.
 
-use serde::{Deserialize, Serialize};
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+use serde::{de::Visitor, Deserialize, Deserializer, Serialize, Serializer};
 
 /// An object with hexadecimal color properties (#RRGGBB).
 #[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
@@ -21,3 +26,217 @@ pub struct Colors {
     /// The color of the visual 'beep' when there is no sound (the sound timer is zero).
     pub silence: Option<String>,
 }
+
+impl Colors {
+    /// The default pixel colors, as used by Octo: the background, plane 1, plane 2 and both planes.
+    pub const DEFAULT_PIXELS: [Rgb; 4] = [
+        Rgb::new(0x99, 0x66, 0x00),
+        Rgb::new(0xff, 0xcc, 0x00),
+        Rgb::new(0xff, 0x66, 0x00),
+        Rgb::new(0x66, 0x22, 0x00),
+    ];
+
+    /// The default buzzer color, as used by Octo.
+    pub const DEFAULT_BUZZER: Rgb = Rgb::new(0xff, 0xaa, 0x00);
+
+    /// The default silence color, as used by Octo.
+    pub const DEFAULT_SILENCE: Rgb = Rgb::new(0x00, 0x00, 0x00);
+
+    /// The color of the pixel state with binary value `index`, or `None` if it is missing or not a
+    /// valid color.
+    pub fn pixel(&self, index: usize) -> Option<Rgb> {
+        self.pixels.as_ref()?.get(index)?.parse().ok()
+    }
+
+    /// The buzzer color, or `None` if it is missing or not a valid color.
+    pub fn buzzer_color(&self) -> Option<Rgb> {
+        self.buzzer.as_deref()?.parse().ok()
+    }
+
+    /// The silence color, or `None` if it is missing or not a valid color.
+    pub fn silence_color(&self) -> Option<Rgb> {
+        self.silence.as_deref()?.parse().ok()
+    }
+
+    /// Resolve every color needed to draw a display with `planes` bitplanes, such as 1 for CHIP-8,
+    /// 2 for XO-CHIP or 8 for the 256 colors of MEGA-CHIP. `planes` is clamped between 1 and 8.
+    ///
+    /// Colors that are missing or invalid fall back to [Colors::DEFAULT_PIXELS],
+    /// [Colors::DEFAULT_BUZZER] and [Colors::DEFAULT_SILENCE]. With more than 2 planes there are
+    /// more colors than defaults, so missing pixel colors fall back to a grayscale ramp from black
+    /// for `0` to white for the last color instead.
+    pub fn palette(&self, planes: u8) -> Palette {
+        let count = 1usize << planes.clamp(1, 8);
+        let pixels = (0..count)
+            .map(|index| {
+                self.pixel(index)
+                    .unwrap_or_else(|| match Self::DEFAULT_PIXELS.get(index) {
+                        Some(&default) if count <= Self::DEFAULT_PIXELS.len() => default,
+                        _ => {
+                            let value = (index * 255 / (count - 1)) as u8;
+                            Rgb::new(value, value, value)
+                        }
+                    })
+            })
+            .collect();
+
+        Palette {
+            pixels,
+            buzzer: self.buzzer_color().unwrap_or(Self::DEFAULT_BUZZER),
+            silence: self.silence_color().unwrap_or(Self::DEFAULT_SILENCE),
+        }
+    }
+}
+
+/// Every color needed to draw a display, resolved by [Colors::palette].
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Palette {
+    /// The color of every pixel state, indexed by its binary value.
+    pub pixels: Vec<Rgb>,
+
+    /// The color of the visual 'beep' while the sound timer is running.
+    pub buzzer: Rgb,
+
+    /// The color of the visual 'beep' when there is no sound.
+    pub silence: Rgb,
+}
+
+/// A color without transparency, parsed from hexadecimal notation like `#rgb` or `#rrggbb`.
+///
+/// ```rust
+/// # use chip8_db::color::Rgb;
+/// #
+/// let color: Rgb = "#FC0".parse().unwrap();
+///
+/// assert_eq!([0xff, 0xcc, 0x00], color.to_array());
+/// assert_eq!(0xffcc00, color.to_u32());
+/// assert_eq!("#ffcc00", color.to_string());
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Rgb {
+    /// The red component.
+    pub r: u8,
+
+    /// The green component.
+    pub g: u8,
+
+    /// The blue component.
+    pub b: u8,
+}
+
+impl Rgb {
+    /// Create a color from its components.
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Rgb { r, g, b }
+    }
+
+    /// Create a color from an integer in `0xRRGGBB` format, ignoring the highest byte.
+    pub const fn from_u32(rgb: u32) -> Self {
+        Rgb::new((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8)
+    }
+
+    /// The components of this color as `[r, g, b]`.
+    pub const fn to_array(self) -> [u8; 3] {
+        [self.r, self.g, self.b]
+    }
+
+    /// This color as an integer in `0xRRGGBB` format.
+    pub const fn to_u32(self) -> u32 {
+        (self.r as u32) << 16 | (self.g as u32) << 8 | self.b as u32
+    }
+}
+
+impl From<[u8; 3]> for Rgb {
+    fn from([r, g, b]: [u8; 3]) -> Self {
+        Rgb::new(r, g, b)
+    }
+}
+
+impl From<Rgb> for [u8; 3] {
+    fn from(color: Rgb) -> Self {
+        color.to_array()
+    }
+}
+
+impl Display for Rgb {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{:06x}", self.to_u32())
+    }
+}
+
+/// The error returned when a string is not a valid [Rgb] color.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseColorError {
+    input: String,
+}
+
+impl Display for ParseColorError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:?} is not a color in #rgb, #rrggbb or #rrggbbaa notation",
+            self.input
+        )
+    }
+}
+
+impl std::error::Error for ParseColorError {}
+
+impl FromStr for Rgb {
+    type Err = ParseColorError;
+
+    /// Parse a color in `#rgb`, `#rrggbb` or `#rrggbbaa` notation, in any case. The `#` is
+    /// optional, and the alpha channel is ignored.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseColorError {
+            input: s.to_owned(),
+        };
+        let hex = s.strip_prefix('#').unwrap_or(s);
+
+        if !hex.bytes().all(|digit| digit.is_ascii_hexdigit()) {
+            return Err(error());
+        }
+
+        let value = u32::from_str_radix(hex, 16).map_err(|_| error())?;
+
+        match hex.len() {
+            3 => {
+                let expand = |digit: u32| (digit & 0xf) as u8 * 0x11;
+                Ok(Rgb::new(
+                    expand(value >> 8),
+                    expand(value >> 4),
+                    expand(value),
+                ))
+            }
+            6 => Ok(Rgb::from_u32(value)),
+            8 => Ok(Rgb::from_u32(value >> 8)),
+            _ => Err(error()),
+        }
+    }
+}
+
+impl Serialize for Rgb {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Rgb {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct RgbVisitor;
+
+        impl<'de> Visitor<'de> for RgbVisitor {
+            type Value = Rgb;
+
+            fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+                write!(f, "a color in #rgb, #rrggbb or #rrggbbaa notation")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                v.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_str(RgbVisitor)
+    }
+}
@@ -0,0 +1,305 @@
+//! Definitions related to identifying ROMs by other hash algorithms than SHA-1, enabled with the
+//! `alt-hashes` feature.
+//!
+//! Other ROM catalogs, such as No-Intro style DAT files and RetroArch playlists, identify files by
+//! their CRC32 or MD5. Since the database only lists SHA-1 hashes, a [DigestIndex] is built from a
+//! local ROM collection: every ROM that is recognized by its SHA-1 hash gets hashed with every
+//! other [HashAlgorithm] as well.
+//!
+//! ```rust,no_run
+//! # use chip8_db::{digest::{DigestIndex, HashAlgorithm}, Database};
+//! # use std::fs::File;
+//! #
+//! let db = Database::new();
+//! let (index, _failed) = DigestIndex::from_dir(&db, "roms/").unwrap();
+//! index.to_writer(File::create("digests.json").unwrap()).unwrap();
+//!
+//! if let Some(metadata) = db.lookup_digest(&index, HashAlgorithm::Crc32, "1a2b3c4d") {
+//!     println!("Title: {:?}", metadata.program.map(|program| &program.title));
+//! }
+//! ```
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Display,
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use md5::Md5;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sha2::{Digest, Sha256};
+
+use crate::{batch::read_roms, hash::RomHash, parse, write, Database, Error, MetadataRef};
+
+/// A hash algorithm that ROMs can be looked up by.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgorithm {
+    /// CRC-32 as used by zip files and DAT files, written as 8 hexadecimal digits.
+    Crc32,
+
+    /// MD5, written as 32 hexadecimal digits.
+    Md5,
+
+    /// SHA-1, the hash used by the database itself, written as 40 hexadecimal digits.
+    Sha1,
+
+    /// SHA-256, written as 64 hexadecimal digits.
+    Sha256,
+}
+
+impl HashAlgorithm {
+    /// Every supported hash algorithm.
+    pub const ALL: [HashAlgorithm; 4] = [
+        HashAlgorithm::Crc32,
+        HashAlgorithm::Md5,
+        HashAlgorithm::Sha1,
+        HashAlgorithm::Sha256,
+    ];
+
+    /// The length of a digest in bytes.
+    pub const fn digest_len(self) -> usize {
+        match self {
+            HashAlgorithm::Crc32 => 4,
+            HashAlgorithm::Md5 => 16,
+            HashAlgorithm::Sha1 => 20,
+            HashAlgorithm::Sha256 => 32,
+        }
+    }
+
+    /// Calculate the digest of a ROM with this algorithm. CRC-32 is returned in big-endian order,
+    /// the way it is usually written.
+    pub fn digest(self, rom: &[u8]) -> Vec<u8> {
+        match self {
+            HashAlgorithm::Crc32 => crc32fast::hash(rom).to_be_bytes().to_vec(),
+            HashAlgorithm::Md5 => Md5::digest(rom).to_vec(),
+            HashAlgorithm::Sha1 => RomHash::from_rom(rom).as_bytes().to_vec(),
+            HashAlgorithm::Sha256 => Sha256::digest(rom).to_vec(),
+        }
+    }
+}
+
+impl Display for HashAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            HashAlgorithm::Crc32 => "CRC32",
+            HashAlgorithm::Md5 => "MD5",
+            HashAlgorithm::Sha1 => "SHA-1",
+            HashAlgorithm::Sha256 => "SHA-256",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+/// The digests of a single ROM by every algorithm other than SHA-1.
+#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct Digests {
+    /// The CRC-32 of the ROM, in big-endian order.
+    #[serde(with = "hex")]
+    pub crc32: [u8; 4],
+
+    /// The MD5 hash of the ROM.
+    #[serde(with = "hex")]
+    pub md5: [u8; 16],
+
+    /// The SHA-256 hash of the ROM.
+    #[serde(with = "hex")]
+    pub sha256: [u8; 32],
+}
+
+impl Digests {
+    /// Calculate every digest of a ROM.
+    pub fn from_rom(rom: &[u8]) -> Self {
+        Digests {
+            crc32: crc32fast::hash(rom).to_be_bytes(),
+            md5: Md5::digest(rom).into(),
+            sha256: Sha256::digest(rom).into(),
+        }
+    }
+
+    /// The digest by a specific algorithm, or [None] for SHA-1, which is the key of the index.
+    pub fn get(&self, algorithm: HashAlgorithm) -> Option<&[u8]> {
+        match algorithm {
+            HashAlgorithm::Crc32 => Some(&self.crc32),
+            HashAlgorithm::Md5 => Some(&self.md5),
+            HashAlgorithm::Sha1 => None,
+            HashAlgorithm::Sha256 => Some(&self.sha256),
+        }
+    }
+}
+
+/// A sidecar index of alternate digests for known ROMs, which can be saved and loaded as JSON so
+/// it only needs to be built once.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(
+    from = "BTreeMap<RomHash, Digests>",
+    into = "BTreeMap<RomHash, Digests>"
+)]
+pub struct DigestIndex {
+    digests: BTreeMap<RomHash, Digests>,
+    hashes: HashMap<(HashAlgorithm, Vec<u8>), RomHash>,
+}
+
+impl DigestIndex {
+    /// Create an empty index.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build an index from every ROM file directly inside a directory that is recognized by `db`,
+    /// going by [ROM_EXTENSIONS](crate::batch::ROM_EXTENSIONS).
+    ///
+    /// An error is only returned if the directory itself can't be read. Files and entries that
+    /// can't be read are skipped and returned along with the index, like
+    /// [BatchSummary.failed](crate::batch::BatchSummary::failed).
+    pub fn from_dir(
+        db: &Database,
+        path: impl AsRef<Path>,
+    ) -> io::Result<(Self, Vec<(PathBuf, io::Error)>)> {
+        let mut index = Self::new();
+        let failed = read_roms(path.as_ref(), |rom| {
+            index.insert(db, rom);
+        })?;
+
+        Ok((index, failed))
+    }
+
+    /// Calculate the digests of a ROM if its SHA-1 hash is in the database, returning whether it
+    /// was added.
+    pub fn insert(&mut self, db: &Database, rom: &[u8]) -> bool {
+        let hash = RomHash::from_rom(rom);
+
+        if db.get_rom(hash).is_none() {
+            return false;
+        }
+
+        self.insert_digests(hash, Digests::from_rom(rom));
+
+        true
+    }
+
+    /// The digests of a known ROM.
+    pub fn get(&self, hash: RomHash) -> Option<&Digests> {
+        self.digests.get(&hash)
+    }
+
+    /// Find the SHA-1 hash of a ROM in the index by its digest in any supported algorithm.
+    pub fn find(&self, algorithm: HashAlgorithm, digest: &[u8]) -> Option<RomHash> {
+        if algorithm == HashAlgorithm::Sha1 {
+            return <[u8; 20]>::try_from(digest)
+                .ok()
+                .map(RomHash)
+                .filter(|hash| self.digests.contains_key(hash));
+        }
+
+        self.hashes.get(&(algorithm, digest.to_vec())).copied()
+    }
+
+    /// Find the SHA-1 hash of a ROM in the index by its digest in any supported algorithm, written
+    /// in hexadecimal in either lowercase or uppercase.
+    pub fn find_hex(&self, algorithm: HashAlgorithm, digest: &str) -> Option<RomHash> {
+        let mut bytes = [0u8; 32];
+        let digest = base16ct::mixed::decode(digest, &mut bytes).ok()?;
+
+        self.find(algorithm, digest)
+    }
+
+    /// The number of ROMs in the index.
+    pub fn len(&self) -> usize {
+        self.digests.len()
+    }
+
+    /// Whether the index contains no ROMs at all.
+    pub fn is_empty(&self) -> bool {
+        self.digests.is_empty()
+    }
+
+    /// Load an index previously saved with [DigestIndex::to_writer].
+    pub fn from_reader(reader: impl Read) -> Result<Self, Error> {
+        parse("digests.json", reader)
+    }
+
+    /// Save the index as JSON.
+    pub fn to_writer(&self, writer: impl Write) -> Result<(), Error> {
+        write("digests.json", writer, self)
+    }
+
+    fn insert_digests(&mut self, hash: RomHash, digests: Digests) {
+        for algorithm in HashAlgorithm::ALL {
+            if let Some(digest) = digests.get(algorithm) {
+                self.hashes.insert((algorithm, digest.to_vec()), hash);
+            }
+        }
+
+        self.digests.insert(hash, digests);
+    }
+}
+
+impl From<BTreeMap<RomHash, Digests>> for DigestIndex {
+    fn from(digests: BTreeMap<RomHash, Digests>) -> Self {
+        let mut index = Self::new();
+
+        for (hash, digests) in digests {
+            index.insert_digests(hash, digests);
+        }
+
+        index
+    }
+}
+
+impl From<DigestIndex> for BTreeMap<RomHash, Digests> {
+    fn from(index: DigestIndex) -> Self {
+        index.digests
+    }
+}
+
+impl Database {
+    /// Lookup the metadata for a ROM by its digest in any supported algorithm, written in
+    /// hexadecimal, borrowing it from the database instead of cloning it.
+    ///
+    /// Returns [None] if the digest isn't valid hexadecimal or isn't in `index`.
+    pub fn lookup_digest(
+        &self,
+        index: &DigestIndex,
+        algorithm: HashAlgorithm,
+        digest: &str,
+    ) -> Option<MetadataRef<'_>> {
+        index
+            .find_hex(algorithm, digest)
+            .map(|hash| self.lookup(hash))
+    }
+}
+
+/// Serialize fixed-size digests as lowercase hexadecimal strings.
+mod hex {
+    use super::*;
+
+    use serde::{de::Error as _, ser::Error as _};
+
+    pub fn serialize<S: Serializer, const N: usize>(
+        bytes: &[u8; N],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut buf = [0u8; 64];
+        let hex = base16ct::lower::encode_str(bytes, &mut buf).map_err(S::Error::custom)?;
+
+        serializer.serialize_str(hex)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, const N: usize>(
+        deserializer: D,
+    ) -> Result<[u8; N], D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        let mut bytes = [0u8; N];
+
+        match base16ct::mixed::decode(&hex, &mut bytes) {
+            Ok(decoded) if decoded.len() == N => Ok(bytes),
+            _ => Err(D::Error::custom(format!(
+                "{hex:?} is not a digest of {} hexadecimal digits",
+                N * 2
+            ))),
+        }
+    }
+}
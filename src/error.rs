@@ -0,0 +1,66 @@
+//! Definitions related to errors when loading the database.
+
+use std::{fmt::Display, io, path::PathBuf};
+
+use crate::hash::RomHash;
+
+/// Errors that can occur while loading the CHIP-8 database.
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum Error {
+    /// One of the database files could not be read.
+    Io(io::Error),
+
+    /// One of the database files does not exist.
+    MissingFile(PathBuf),
+
+    /// One of the database files was not in the expected schema.
+    Json {
+        /// The name of the file that failed to parse, such as `programs.json`.
+        file: &'static str,
+
+        /// The underlying parse error, including the line and column.
+        source: serde_json::Error,
+    },
+
+    /// A hash in `sha1-hashes.json` points at a program that does not exist in `programs.json`.
+    InvalidIndex {
+        /// The hash with the invalid index.
+        hash: RomHash,
+
+        /// The index into the list of programs, which is out of bounds.
+        index: usize,
+    },
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Io(error) => write!(f, "failed to read database file: {error}"),
+            Error::MissingFile(path) => write!(f, "missing database file: {}", path.display()),
+            Error::Json { file, source } => write!(f, "failed to parse {file}: {source}"),
+            Error::InvalidIndex { hash, index } => {
+                write!(
+                    f,
+                    "hash {hash} points at program {index}, which does not exist"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(error) => Some(error),
+            Error::Json { source, .. } => Some(source),
+            Error::MissingFile(_) | Error::InvalidIndex { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Error::Io(error)
+    }
+}
@@ -0,0 +1,203 @@
+//! Definitions related to listing the ROMs in the database that match a set of conditions.
+//!
+//! ```rust
+//! # use chip8_db::{platform::Platform, release::ReleaseDate, Database};
+//! #
+//! let db = Database::new();
+//!
+//! // All XO-CHIP ROMs with two-player controls released in 2021 or later
+//! for (program, hash, rom) in db
+//!     .filter()
+//!     .platform(Platform::XOChip)
+//!     .two_players()
+//!     .released_since(ReleaseDate::Year(2021))
+//! {
+//!     println!("{} ({hash}): {:?}", program.title, rom.file_name);
+//! }
+//! ```
+
+use crate::{
+    font::FontStyle, hash::RomHash, input::TouchInputMode, origin::OriginType, platform::Platform,
+    program::Program, quirk::Quirk, release::ReleaseDate, rom::Rom, rotation::ScreenRotation,
+    Database,
+};
+
+/// A composable set of conditions on ROMs, created with [Database::filter].
+///
+/// Every condition must hold for a ROM to match. Iterating over the filter yields every matching
+/// ROM along with its program and hash, in the order of [Database.programs](Database::programs)
+/// and then by hash.
+#[derive(Clone, Debug)]
+#[must_use = "a filter does nothing until it is iterated over"]
+pub struct Filter<'db> {
+    db: &'db Database,
+    platforms: Vec<Platform>,
+    origin_type: Option<OriginType>,
+    author: Option<String>,
+    released_since: Option<ReleaseDate>,
+    released_before: Option<ReleaseDate>,
+    two_players: bool,
+    touch_input_mode: Option<TouchInputMode>,
+    font_style: Option<FontStyle>,
+    screen_rotation: Option<ScreenRotation>,
+    quirks: Vec<(Quirk, bool)>,
+}
+
+impl Database {
+    /// Start a filter that matches every ROM in the database, to be narrowed down with conditions.
+    pub fn filter(&self) -> Filter<'_> {
+        Filter {
+            db: self,
+            platforms: Vec::new(),
+            origin_type: None,
+            author: None,
+            released_since: None,
+            released_before: None,
+            two_players: false,
+            touch_input_mode: None,
+            font_style: None,
+            screen_rotation: None,
+            quirks: Vec::new(),
+        }
+    }
+}
+
+impl<'db> Filter<'db> {
+    /// Only match ROMs that list `platform` among the platforms they run on.
+    pub fn platform(mut self, platform: Platform) -> Self {
+        self.platforms.push(platform);
+        self
+    }
+
+    /// Only match ROMs of programs with this kind of origin.
+    pub fn origin_type(mut self, origin_type: OriginType) -> Self {
+        self.origin_type = Some(origin_type);
+        self
+    }
+
+    /// Only match ROMs where either the ROM or its program lists `author`, ignoring case.
+    pub fn author(mut self, author: impl Into<String>) -> Self {
+        self.author = Some(author.into());
+        self
+    }
+
+    /// Only match ROMs released on or after `date`.
+    ///
+    /// The release date of the ROM is used if it's known, otherwise that of its program. ROMs
+    /// without a known or valid release date never match. Dates are compared as described on
+    /// [ReleaseDate], so a ROM released in `2021-03` is released since `2021`.
+    pub fn released_since(mut self, date: ReleaseDate) -> Self {
+        self.released_since = Some(date);
+        self
+    }
+
+    /// Only match ROMs released before `date`.
+    ///
+    /// The release date of the ROM is used if it's known, otherwise that of its program. ROMs
+    /// without a known or valid release date never match. Dates are compared as described on
+    /// [ReleaseDate], so a ROM released in `2021-03` is not released before `2021`.
+    pub fn released_before(mut self, date: ReleaseDate) -> Self {
+        self.released_before = Some(date);
+        self
+    }
+
+    /// Only match ROMs with keys mapped for a second player.
+    pub fn two_players(mut self) -> Self {
+        self.two_players = true;
+        self
+    }
+
+    /// Only match ROMs that prefer this touch input mode.
+    pub fn touch_input_mode(mut self, mode: TouchInputMode) -> Self {
+        self.touch_input_mode = Some(mode);
+        self
+    }
+
+    /// Only match ROMs that prefer this font style.
+    pub fn font_style(mut self, style: FontStyle) -> Self {
+        self.font_style = Some(style);
+        self
+    }
+
+    /// Only match ROMs that prefer this screen rotation.
+    pub fn screen_rotation(mut self, rotation: ScreenRotation) -> Self {
+        self.screen_rotation = Some(rotation);
+        self
+    }
+
+    /// Only match ROMs that explicitly set `quirk` to `enabled` on any of their quirky platforms.
+    pub fn quirk(mut self, quirk: Quirk, enabled: bool) -> Self {
+        self.quirks.push((quirk, enabled));
+        self
+    }
+
+    /// Whether a ROM of a program matches every condition of this filter.
+    pub fn matches(&self, program: &Program, rom: &Rom) -> bool {
+        let release = rom.release_date().or_else(|| program.release_date());
+        let has_author = |authors: &Option<Vec<String>>, author: &str| {
+            authors
+                .iter()
+                .flatten()
+                .any(|listed| listed.eq_ignore_ascii_case(author))
+        };
+
+        self.platforms
+            .iter()
+            .all(|platform| rom.platforms.contains(platform))
+            && self.origin_type.map_or(true, |origin_type| {
+                program
+                    .origin
+                    .as_ref()
+                    .is_some_and(|origin| origin.origin_type == Some(origin_type))
+            })
+            && self.author.as_ref().map_or(true, |author| {
+                has_author(&program.authors, author) || has_author(&rom.authors, author)
+            })
+            && self.released_since.map_or(true, |since| {
+                release.is_some_and(|release| release >= since)
+            })
+            && self.released_before.map_or(true, |before| {
+                release.is_some_and(|release| release < before)
+            })
+            && (!self.two_players || rom.keys.iter().flatten().any(|(key, _)| key.is_player2()))
+            && self
+                .touch_input_mode
+                .map_or(true, |mode| rom.touch_input_mode == Some(mode))
+            && self
+                .font_style
+                .map_or(true, |style| rom.font_style == Some(style))
+            && self
+                .screen_rotation
+                .map_or(true, |rotation| rom.screen_rotation == Some(rotation))
+            && self.quirks.iter().all(|&(quirk, enabled)| {
+                rom.quirky_platforms
+                    .iter()
+                    .flatten()
+                    .any(|(_, quirks)| quirks.get(quirk) == Some(enabled))
+            })
+    }
+
+    /// Iterate over every matching ROM along with its program and hash.
+    pub fn iter(&self) -> impl Iterator<Item = (&'db Program, RomHash, &'db Rom)> + '_ {
+        self.db.programs.iter().flat_map(move |program| {
+            let mut roms: Vec<_> = program
+                .roms
+                .iter()
+                .filter(|(_, rom)| self.matches(program, rom))
+                .map(|(&hash, rom)| (program, hash, rom))
+                .collect();
+
+            roms.sort_unstable_by_key(|&(_, hash, _)| hash);
+            roms
+        })
+    }
+}
+
+impl<'db> IntoIterator for Filter<'db> {
+    type Item = (&'db Program, RomHash, &'db Rom);
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter().collect::<Vec<_>>().into_iter()
+    }
+}
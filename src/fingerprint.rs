@@ -0,0 +1,294 @@
+//! Definitions related to identifying unknown ROMs by their similarity to known ones.
+//!
+//! The database only stores hashes, so patched or slightly modified dumps of well-known games
+//! can't be recognized from it alone. Instead, a [FingerprintIndex] is built from a local ROM
+//! collection: every ROM that is recognized by its SHA-1 hash gets fingerprinted, and unknown ROMs
+//! are compared against those fingerprints.
+//!
+//! ```rust,no_run
+//! # use chip8_db::{fingerprint::FingerprintIndex, Database};
+//! #
+//! let db = Database::new();
+//! let (index, _failed) = FingerprintIndex::from_dir(&db, "roms/").unwrap();
+//! let rom = std::fs::read("roms/patched.ch8").unwrap();
+//!
+//! for candidate in db.closest_matches(&index, &rom, 3) {
+//!     println!("{:.0}% {}", candidate.score * 100.0, candidate.program.title);
+//! }
+//! ```
+
+use std::{
+    collections::HashMap,
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    batch::read_roms, hash::RomHash, parse, program::Program, rom::Rom, write, Database, Error,
+};
+
+/// The size of the window used by the rolling hash that splits ROMs into chunks.
+const WINDOW: usize = 8;
+
+/// A chunk ends wherever the rolling hash has all of these bits cleared, for an average chunk size
+/// of 32 bytes.
+const BOUNDARY_MASK: u32 = 0x1f;
+
+/// How much each part of a fingerprint contributes to the similarity score.
+const CHUNK_WEIGHT: f64 = 0.6;
+const OPCODE_WEIGHT: f64 = 0.25;
+const SIZE_WEIGHT: f64 = 0.15;
+
+/// A summary of the contents of a ROM that changes only a little when the ROM is patched.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Fingerprint {
+    /// The size of the ROM in bytes.
+    pub size: usize,
+
+    /// How often each high nibble appears at the start of a two-byte instruction, which roughly
+    /// counts the kinds of opcodes used.
+    pub opcodes: [u32; 16],
+
+    /// The sorted, deduplicated hashes of content-defined chunks of the ROM.
+    ///
+    /// Chunk boundaries are picked by a rolling hash, so an edit only changes the chunks around it
+    /// rather than shifting every chunk after it.
+    pub chunks: Vec<u32>,
+}
+
+impl Fingerprint {
+    /// Calculate the fingerprint of a ROM.
+    pub fn from_rom(rom: &[u8]) -> Self {
+        let mut opcodes = [0; 16];
+
+        for instruction in rom.chunks(2) {
+            opcodes[usize::from(instruction[0] >> 4)] += 1;
+        }
+
+        let mut chunks = Vec::new();
+        let mut start = 0;
+        let mut rolling: u32 = 0;
+
+        for (i, &byte) in rom.iter().enumerate() {
+            rolling = rolling.rotate_left(1) ^ u32::from(byte);
+
+            if i >= WINDOW {
+                rolling ^= u32::from(rom[i - WINDOW]).rotate_left(WINDOW as u32);
+            }
+
+            if i + 1 - start >= WINDOW && scramble(rolling) & BOUNDARY_MASK == 0 {
+                chunks.push(fnv1a(&rom[start..=i]));
+                start = i + 1;
+            }
+        }
+
+        if start < rom.len() {
+            chunks.push(fnv1a(&rom[start..]));
+        }
+
+        chunks.sort_unstable();
+        chunks.dedup();
+
+        Fingerprint {
+            size: rom.len(),
+            opcodes,
+            chunks,
+        }
+    }
+
+    /// How similar two fingerprints are, from `0.0` for nothing in common to `1.0` for identical.
+    pub fn similarity(&self, other: &Fingerprint) -> f64 {
+        CHUNK_WEIGHT * jaccard(&self.chunks, &other.chunks)
+            + OPCODE_WEIGHT * cosine(&self.opcodes, &other.opcodes)
+            + SIZE_WEIGHT * ratio(self.size, other.size)
+    }
+}
+
+/// A collection of fingerprints of known ROMs, which can be saved and loaded as JSON so it only
+/// needs to be built once.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct FingerprintIndex {
+    /// The fingerprint of every known ROM, by its hash.
+    pub fingerprints: HashMap<RomHash, Fingerprint>,
+}
+
+impl FingerprintIndex {
+    /// Create an empty index.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build an index from every ROM file directly inside a directory that is recognized by `db`,
+    /// going by [ROM_EXTENSIONS](crate::batch::ROM_EXTENSIONS).
+    ///
+    /// An error is only returned if the directory itself can't be read. Files and entries that
+    /// can't be read are skipped and returned along with the index, like
+    /// [BatchSummary.failed](crate::batch::BatchSummary::failed).
+    pub fn from_dir(
+        db: &Database,
+        path: impl AsRef<Path>,
+    ) -> io::Result<(Self, Vec<(PathBuf, io::Error)>)> {
+        let mut index = Self::new();
+        let failed = read_roms(path.as_ref(), |rom| {
+            index.insert(db, rom);
+        })?;
+
+        Ok((index, failed))
+    }
+
+    /// Fingerprint a ROM if its hash is in the database, returning whether it was added.
+    pub fn insert(&mut self, db: &Database, rom: &[u8]) -> bool {
+        let hash = RomHash::from_rom(rom);
+
+        if db.get_rom(hash).is_none() {
+            return false;
+        }
+
+        self.fingerprints.insert(hash, Fingerprint::from_rom(rom));
+
+        true
+    }
+
+    /// Load an index previously saved with [FingerprintIndex::to_writer].
+    pub fn from_reader(reader: impl Read) -> Result<Self, Error> {
+        parse("fingerprints.json", reader)
+    }
+
+    /// Save the index as JSON.
+    pub fn to_writer(&self, writer: impl Write) -> Result<(), Error> {
+        write("fingerprints.json", writer, self)
+    }
+}
+
+/// A known program that an unknown ROM might be a version of.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Candidate<'db> {
+    /// The hash of the most similar known ROM.
+    pub hash: RomHash,
+
+    /// The program the most similar known ROM belongs to.
+    pub program: &'db Program,
+
+    /// The most similar known ROM.
+    pub rom: &'db Rom,
+
+    /// How similar the ROMs are, from `0.0` for nothing in common to `1.0` for identical.
+    pub score: f64,
+}
+
+impl Database {
+    /// Find the programs that an unknown ROM is most similar to, best first, with at most one
+    /// candidate per program.
+    ///
+    /// A ROM that is recognized by its hash is returned as the only candidate, with a score of
+    /// `1.0`, unless `limit` is `0`. Fingerprints in `index` of ROMs that are no longer in the
+    /// database are ignored.
+    pub fn closest_matches(
+        &self,
+        index: &FingerprintIndex,
+        rom: &[u8],
+        limit: usize,
+    ) -> Vec<Candidate<'_>> {
+        let hash = RomHash::from_rom(rom);
+
+        if let (Some(program), Some(known)) = (self.get_program(hash), self.get_rom(hash)) {
+            let candidate = Candidate {
+                hash,
+                program,
+                rom: known,
+                score: 1.0,
+            };
+
+            return std::iter::once(candidate).take(limit).collect();
+        }
+
+        let fingerprint = Fingerprint::from_rom(rom);
+        let mut best: HashMap<usize, Candidate<'_>> = HashMap::new();
+
+        for (&hash, known) in &index.fingerprints {
+            let Some(&program_index) = self.hashes.get(&hash) else {
+                continue;
+            };
+            let Some(program) = self.programs.get(program_index) else {
+                continue;
+            };
+            let Some(rom) = program.roms.get(&hash) else {
+                continue;
+            };
+
+            let score = fingerprint.similarity(known);
+            let candidate = Candidate {
+                hash,
+                program,
+                rom,
+                score,
+            };
+
+            best.entry(program_index)
+                .and_modify(|best| {
+                    if score > best.score || (score == best.score && hash < best.hash) {
+                        *best = candidate;
+                    }
+                })
+                .or_insert(candidate);
+        }
+
+        let mut candidates: Vec<_> = best.into_values().collect();
+
+        candidates.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.hash.cmp(&b.hash)));
+        candidates.truncate(limit);
+
+        candidates
+    }
+}
+
+/// Spread the bits of the rolling hash so the boundary mask doesn't only look at the last bytes.
+fn scramble(hash: u32) -> u32 {
+    hash.wrapping_mul(0x9e37_79b1) >> 16
+}
+
+fn fnv1a(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0x811c_9dc5, |hash, &byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    })
+}
+
+/// The share of chunks two sorted, deduplicated lists have in common.
+fn jaccard(a: &[u32], b: &[u32]) -> f64 {
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+
+    let shared = a
+        .iter()
+        .filter(|chunk| b.binary_search(chunk).is_ok())
+        .count();
+
+    shared as f64 / (a.len() + b.len() - shared) as f64
+}
+
+fn cosine(a: &[u32; 16], b: &[u32; 16]) -> f64 {
+    let dot: f64 = a
+        .iter()
+        .zip(b)
+        .map(|(&a, &b)| f64::from(a) * f64::from(b))
+        .sum();
+    let norm = |v: &[u32; 16]| v.iter().map(|&x| f64::from(x).powi(2)).sum::<f64>().sqrt();
+
+    let norms = norm(a) * norm(b);
+
+    if norms == 0.0 {
+        f64::from(u8::from(a == b))
+    } else {
+        dot / norms
+    }
+}
+
+fn ratio(a: usize, b: usize) -> f64 {
+    match a.max(b) {
+        0 => 1.0,
+        max => a.min(b) as f64 / max as f64,
+    }
+}
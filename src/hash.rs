@@ -0,0 +1,149 @@
+//! Definitions related to ROM hashes.
+
+use std::{
+    fmt::{Debug, Display, Formatter},
+    io::{self, Read},
+    str::FromStr,
+};
+
+use serde::{de::Visitor, Deserialize, Deserializer, Serialize, Serializer};
+use sha1::{Digest, Sha1};
+
+/// The SHA-1 hash of a ROM file, by which it is identified in the database.
+///
+/// Hashes are parsed from hexadecimal strings in any case, and displayed in lowercase as they
+/// appear in `sha1-hashes.json`.
+///
+/// ```rust
+/// # use chip8_db::hash::RomHash;
+/// #
+/// let lower: RomHash = "0df2789f661358d8f7370e6cf93490c5bcd44b01".parse().unwrap();
+/// let upper: RomHash = "0DF2789F661358D8F7370E6CF93490C5BCD44B01".parse().unwrap();
+///
+/// assert_eq!(lower, upper);
+/// assert_eq!("0df2789f661358d8f7370e6cf93490c5bcd44b01", upper.to_string());
+/// ```
+#[derive(Clone, Copy, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct RomHash(pub [u8; 20]);
+
+impl RomHash {
+    /// Calculate the hash of a ROM file.
+    pub fn from_rom(rom: &[u8]) -> Self {
+        RomHash(Sha1::digest(rom).into())
+    }
+
+    /// Calculate the hash of a ROM by reading it to the end in chunks, so large files or archive
+    /// entries don't have to be held in memory.
+    pub fn from_reader(mut reader: impl Read) -> io::Result<Self> {
+        let mut hasher = Sha1::new();
+        let mut buf = [0u8; 8192];
+
+        loop {
+            match reader.read(&mut buf) {
+                Ok(0) => return Ok(RomHash(hasher.finalize().into())),
+                Ok(read) => hasher.update(&buf[..read]),
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+                Err(error) => return Err(error),
+            }
+        }
+    }
+
+    /// The raw bytes of the hash.
+    pub const fn as_bytes(&self) -> &[u8; 20] {
+        &self.0
+    }
+}
+
+impl From<[u8; 20]> for RomHash {
+    fn from(bytes: [u8; 20]) -> Self {
+        RomHash(bytes)
+    }
+}
+
+impl AsRef<[u8]> for RomHash {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl Display for RomHash {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut buf = [0u8; 40];
+        let hex = base16ct::lower::encode_str(&self.0, &mut buf).map_err(|_| std::fmt::Error)?;
+
+        f.write_str(hex)
+    }
+}
+
+impl Debug for RomHash {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "RomHash({self})")
+    }
+}
+
+/// The error returned when a string is not a valid [RomHash].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseRomHashError {
+    input: String,
+}
+
+impl Display for ParseRomHashError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:?} is not a SHA-1 hash of 40 hexadecimal digits",
+            self.input
+        )
+    }
+}
+
+impl std::error::Error for ParseRomHashError {}
+
+impl FromStr for RomHash {
+    type Err = ParseRomHashError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut bytes = [0u8; 20];
+
+        match base16ct::mixed::decode(s, &mut bytes) {
+            Ok(decoded) if decoded.len() == 20 => Ok(RomHash(bytes)),
+            _ => Err(ParseRomHashError {
+                input: s.to_owned(),
+            }),
+        }
+    }
+}
+
+impl TryFrom<&str> for RomHash {
+    type Error = ParseRomHashError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl Serialize for RomHash {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for RomHash {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct RomHashVisitor;
+
+        impl<'de> Visitor<'de> for RomHashVisitor {
+            type Value = RomHash;
+
+            fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+                write!(f, "a SHA-1 hash of 40 hexadecimal digits")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                v.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_str(RomHashVisitor)
+    }
+}
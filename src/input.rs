@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 
 /// A list of all named input keys in the DB schema.
 #[non_exhaustive]
-#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub enum Keymap {
     #[serde(rename = "up")]
     P1Up,
@@ -43,9 +43,24 @@ pub enum Keymap {
     P2B,
 }
 
+impl Keymap {
+    /// Whether this key is part of the controls for a second player.
+    pub const fn is_player2(self) -> bool {
+        matches!(
+            self,
+            Keymap::P2Up
+                | Keymap::P2Down
+                | Keymap::P2Left
+                | Keymap::P2Right
+                | Keymap::P2A
+                | Keymap::P2B
+        )
+    }
+}
+
 /// The preferred touch input mode, values as used by Octo.
 #[non_exhaustive]
-#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum TouchInputMode {
     /// No defined touch input mode exists for this ROM.
@@ -21,8 +21,9 @@
 //! // Get metadata from a rom directly
 //! let metadata = db.get_metadata(&rom);
 //!
-//! // Get metadata from a hash string
-//! let metadata = db.get_metadata_from_hash("0df2789f661358d8f7370e6cf93490c5bcd44b01");
+//! // Get metadata from a hash string, in either lowercase or uppercase
+//! let hash = "0df2789f661358d8f7370e6cf93490c5bcd44b01".parse().unwrap();
+//! let metadata = db.get_metadata_from_hash(hash);
 //! let program = metadata.program.unwrap();
 //!
 //! println!("Title: {} ({})", program.title, metadata.hash);
@@ -31,6 +32,9 @@
 //! if let Some(description) = program.description {
 //!     println!("Description: {description}");
 //! }
+//!
+//! // A shared copy of the database can be borrowed from anywhere in your program
+//! let program = chip8_db::get_program(hash);
 //! ```
 //!
 //! ## Features
@@ -42,26 +46,61 @@
 //! chip_8_database_rs = { version = "2.0.0", features = ["extra-data"] }
 //! ```
 //!
+//! The `static-data` feature generates a static copy of the ROM database at compile time, available
+//! in the [static_data] module, so lookups don't need to parse any JSON at runtime. [Database::new]
+//! still parses the embedded JSON.
+//!
+//! The `alt-hashes` feature adds a sidecar index to lookup ROMs by CRC32, MD5 or SHA-256, in the
+//! [digest] module.
+//!
+//! The `rayon` feature hashes files in parallel when identifying a whole folder of ROMs with
+//! [Database::identify_dir] or [Database::identify_paths].
+//!
 //! [CHIP-8]: https://chip-8.github.io/links/
 //! [CHIP-8 Database]: https://github.com/chip-8/chip-8-database
 
+pub mod batch;
+pub mod binding;
+pub mod capability;
 pub mod color;
+#[cfg(feature = "alt-hashes")]
+pub mod digest;
+pub mod error;
+pub mod filter;
+pub mod fingerprint;
 pub mod font;
+pub mod hash;
 pub mod input;
+mod merge;
 pub mod origin;
 pub mod platform;
 pub mod program;
 pub mod quirk;
+pub mod release;
 pub mod rom;
 pub mod rotation;
+pub mod search;
+#[cfg(feature = "static-data")]
+pub mod static_data;
+pub mod touch;
+pub mod validation;
 
+pub use error::Error;
+
+use hash::RomHash;
 use program::Program;
 use rom::Rom;
-use sha1::{Digest, Sha1};
-use std::collections::HashMap;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, BufReader, ErrorKind, Read, Write},
+    path::Path,
+    sync::OnceLock,
+};
 
 #[cfg(feature = "extra-data")]
-use platform::PlatformDetails;
+use platform::{Platform, PlatformDetails};
 
 #[cfg(feature = "extra-data")]
 use quirk::QuirkDetails;
@@ -73,7 +112,7 @@ pub struct Database {
     pub programs: Vec<Program>,
 
     /// A map of all known ROM hashes, used to index into [programs].
-    pub hashes: HashMap<String, usize>,
+    pub hashes: HashMap<RomHash, usize>,
 
     /// A list of all known CHIP-8 variants.
     #[cfg(feature = "extra-data")]
@@ -84,92 +123,1650 @@ pub struct Database {
     pub quirks: Vec<QuirkDetails>,
 }
 
+const PROGRAMS_JSON: &str = include_str!("../chip-8-database/database/programs.json");
+const HASHES_JSON: &str = include_str!("../chip-8-database/database/sha1-hashes.json");
+
+#[cfg(feature = "extra-data")]
+const PLATFORMS_JSON: &str = include_str!("../chip-8-database/database/platforms.json");
+
+#[cfg(feature = "extra-data")]
+const QUIRKS_JSON: &str = include_str!("../chip-8-database/database/quirks.json");
+
 impl Database {
     /// Create a new instance of the DB. Does not touch the filesystem or network.
+    ///
+    /// This always parses the embedded JSON, even with the `static-data` feature. Use the
+    /// `static_data` module for lookups that don't parse or allocate anything at runtime.
     pub fn new() -> Self {
         // Updating note: Panics if the `.json` files in `../chip-8-database/database/` are not in the
         // expected schema. Update the tests with the new schema and try again.
-        let programs = {
-            let json = include_str!("../chip-8-database/database/programs.json");
+        Self::try_new().expect("the embedded database should never be in an invalid state")
+    }
 
-            serde_json::from_str(json)
-                .expect("programs.json is hardcoded and should never be in an invalid state")
-        };
+    /// Create a new instance of the DB, returning an [Error] instead of panicking if the embedded
+    /// data is invalid. Does not touch the filesystem or network.
+    pub fn try_new() -> Result<Self, Error> {
+        #[cfg(feature = "extra-data")]
+        {
+            Self::from_readers_with_extra_data(
+                PROGRAMS_JSON.as_bytes(),
+                HASHES_JSON.as_bytes(),
+                PLATFORMS_JSON.as_bytes(),
+                QUIRKS_JSON.as_bytes(),
+            )
+        }
 
-        let hashes = {
-            let json = include_str!("../chip-8-database/database/sha1-hashes.json");
+        #[cfg(not(feature = "extra-data"))]
+        Self::from_readers(PROGRAMS_JSON.as_bytes(), HASHES_JSON.as_bytes())
+    }
 
-            serde_json::from_str(json)
-                .expect("sha1-hashes.json is hardcoded and should never be in an invalid state")
+    /// Load the database at runtime from a directory containing `programs.json` and
+    /// `sha1-hashes.json`, such as the `database/` directory of a [CHIP-8 Database][] checkout.
+    ///
+    /// With the `extra-data` feature, `platforms.json` and `quirks.json` are loaded from the same
+    /// directory.
+    ///
+    /// [CHIP-8 Database]: https://github.com/chip-8/chip-8-database
+    pub fn from_dir(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let open = |file_name| {
+            let path = path.join(file_name);
+
+            match File::open(&path) {
+                Ok(file) => Ok(BufReader::new(file)),
+                Err(error) if error.kind() == ErrorKind::NotFound => Err(Error::MissingFile(path)),
+                Err(error) => Err(Error::Io(error)),
+            }
         };
 
+        let programs = open("programs.json")?;
+        let hashes = open("sha1-hashes.json")?;
+
         #[cfg(feature = "extra-data")]
-        let platforms = {
-            let json = include_str!("../chip-8-database/database/platforms.json");
+        {
+            let platforms = open("platforms.json")?;
+            let quirks = open("quirks.json")?;
+
+            Self::from_readers_with_extra_data(programs, hashes, platforms, quirks)
+        }
+
+        #[cfg(not(feature = "extra-data"))]
+        Self::from_readers(programs, hashes)
+    }
+
+    /// Load the database at runtime from the contents of `programs.json` and `sha1-hashes.json`.
+    ///
+    /// With the `extra-data` feature, the embedded platforms and quirks are used. See
+    /// [Database::from_readers_with_extra_data] to replace those as well.
+    pub fn from_readers(programs: impl Read, hashes: impl Read) -> Result<Self, Error> {
+        let db = Database {
+            programs: parse("programs.json", programs)?,
+            hashes: parse("sha1-hashes.json", hashes)?,
+
+            #[cfg(feature = "extra-data")]
+            platforms: parse("platforms.json", PLATFORMS_JSON.as_bytes())?,
 
-            serde_json::from_str(json)
-                .expect("platforms.json is hardcoded and should never be in an invalid state")
+            #[cfg(feature = "extra-data")]
+            quirks: parse("quirks.json", QUIRKS_JSON.as_bytes())?,
         };
 
-        #[cfg(feature = "extra-data")]
-        let quirks = {
-            let json = include_str!("../chip-8-database/database/quirks.json");
+        db.check_indices()?;
+
+        Ok(db)
+    }
 
-            serde_json::from_str(json)
-                .expect("quirks.json is hardcoded and should never be in an invalid state")
+    /// Load the database at runtime from the contents of `programs.json`, `sha1-hashes.json`,
+    /// `platforms.json` and `quirks.json`.
+    #[cfg(feature = "extra-data")]
+    pub fn from_readers_with_extra_data(
+        programs: impl Read,
+        hashes: impl Read,
+        platforms: impl Read,
+        quirks: impl Read,
+    ) -> Result<Self, Error> {
+        let db = Database {
+            programs: parse("programs.json", programs)?,
+            hashes: parse("sha1-hashes.json", hashes)?,
+            platforms: parse("platforms.json", platforms)?,
+            quirks: parse("quirks.json", quirks)?,
         };
 
+        db.check_indices()?;
+
+        Ok(db)
+    }
+
+    /// Ensure every hash points at a program that exists, so lookups can never index out of bounds.
+    fn check_indices(&self) -> Result<(), Error> {
+        match self
+            .hashes
+            .iter()
+            .find(|(_, index)| **index >= self.programs.len())
+        {
+            Some((hash, index)) => Err(Error::InvalidIndex {
+                hash: *hash,
+                index: *index,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Lookup the metadata for a specific ROM file by hashing it.
+    pub fn get_metadata(&self, rom: &[u8]) -> Metadata {
+        self.lookup_rom(rom).into()
+    }
+
+    /// Lookup the metadata for a ROM by hashing everything that can be read from `reader`, without
+    /// loading it into memory all at once.
+    pub fn get_metadata_from_reader(&self, reader: impl Read) -> io::Result<Metadata> {
+        Ok(self.get_metadata_from_hash(RomHash::from_reader(reader)?))
+    }
+
+    /// Lookup the metadata for a ROM file on disk by hashing it.
+    pub fn get_metadata_from_path(&self, path: impl AsRef<Path>) -> io::Result<Metadata> {
+        self.get_metadata_from_reader(File::open(path)?)
+    }
+
+    /// Lookup the metadata for a specific hash.
+    pub fn get_metadata_from_hash(&self, hash: RomHash) -> Metadata {
+        self.lookup(hash).into()
+    }
+
+    /// Lookup the metadata for a specific ROM file by hashing it, borrowing it from the database
+    /// instead of cloning it.
+    pub fn lookup_rom(&self, rom: &[u8]) -> MetadataRef<'_> {
+        self.lookup(RomHash::from_rom(rom))
+    }
+
+    /// Lookup the metadata for a specific hash, borrowing it from the database instead of cloning
+    /// it.
+    pub fn lookup(&self, hash: RomHash) -> MetadataRef<'_> {
+        let program = self.get_program(hash);
+        let rom = program.and_then(|program| program.roms.get(&hash));
+
+        MetadataRef { hash, program, rom }
+    }
+
+    /// Lookup the program for a specific hash, borrowing it from the database.
+    pub fn get_program(&self, hash: RomHash) -> Option<&Program> {
+        self.hashes.get(&hash).and_then(|i| self.programs.get(*i))
+    }
+
+    /// Lookup the ROM-specific metadata for a specific hash, borrowing it from the database.
+    pub fn get_rom(&self, hash: RomHash) -> Option<&Rom> {
+        self.get_program(hash)?.roms.get(&hash)
+    }
+
+    /// Lookup the details of a platform, borrowing them from the database.
+    #[cfg(feature = "extra-data")]
+    pub fn get_platform(&self, platform: Platform) -> Option<&PlatformDetails> {
+        self.platforms.iter().find(|details| details.id == platform)
+    }
+}
+
+/// Get a shared instance of the embedded database, which is created the first time it's used.
+///
+/// This allows frontends, cores, and any other crates in the same program to share a single parsed
+/// copy of the database, rather than each calling [Database::new]. Like [Database::new], it parses
+/// the embedded JSON the first time, even with the `static-data` feature.
+///
+/// ```rust
+/// # use chip8_db::database;
+/// #
+/// let hash = "0df2789f661358d8f7370e6cf93490c5bcd44b01".parse().unwrap();
+///
+/// if let Some(program) = database().get_program(hash) {
+///     println!("Title: {}", program.title);
+/// }
+/// ```
+pub fn database() -> &'static Database {
+    static DATABASE: OnceLock<Database> = OnceLock::new();
+
+    DATABASE.get_or_init(Database::new)
+}
+
+/// Lookup the program for a specific hash in the shared [database].
+pub fn get_program(hash: RomHash) -> Option<&'static Program> {
+    database().get_program(hash)
+}
+
+/// Lookup the ROM-specific metadata for a specific hash in the shared [database].
+pub fn get_rom(hash: RomHash) -> Option<&'static Rom> {
+    database().get_rom(hash)
+}
+
+/// Lookup the metadata for a specific hash in the shared [database].
+pub fn lookup(hash: RomHash) -> MetadataRef<'static> {
+    database().lookup(hash)
+}
+
+/// Parse one of the database files, keeping track of which file failed.
+fn parse<T: DeserializeOwned>(file: &'static str, reader: impl Read) -> Result<T, Error> {
+    serde_json::from_reader(reader).map_err(|source| Error::Json { file, source })
+}
+
+fn write(file: &'static str, writer: impl Write, value: &impl Serialize) -> Result<(), Error> {
+    serde_json::to_writer(writer, value).map_err(|source| {
+        if source.is_io() {
+            Error::Io(source.into())
+        } else {
+            Error::Json { file, source }
+        }
+    })
+}
+
+/// Metadata results from a ROM lookup
+#[derive(Clone, Debug, Default)]
+pub struct Metadata {
+    /// During ROM lookup, this will be populated with the hash used.
+    pub hash: RomHash,
+
+    /// The program matching the listed hash.
+    pub program: Option<Program>,
+
+    /// Any ROM-specific metadata, otherwise defaulting to the values in program.
+    pub rom: Option<Rom>,
+}
+
+impl From<MetadataRef<'_>> for Metadata {
+    fn from(metadata: MetadataRef<'_>) -> Self {
+        Metadata {
+            hash: metadata.hash,
+            program: metadata.program.cloned(),
+            rom: metadata.rom.cloned(),
+        }
+    }
+}
+
+impl<'a> From<&'a Metadata> for MetadataRef<'a> {
+    fn from(metadata: &'a Metadata) -> Self {
+        MetadataRef {
+            hash: metadata.hash,
+            program: metadata.program.as_ref(),
+            rom: metadata.rom.as_ref(),
+        }
+    }
+}
+
+/// Metadata results from a ROM lookup, borrowed from the [Database]. See [Database::lookup].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct MetadataRef<'db> {
+    /// The hash used during ROM lookup.
+    pub hash: RomHash,
+
+    /// The program matching the listed hash.
+    pub program: Option<&'db Program>,
+
+    /// Any ROM-specific metadata, otherwise defaulting to the values in program.
+    pub rom: Option<&'db Rom>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Build a database from parts, regardless of which features are enabled.
+    #[allow(clippy::needless_update)]
+    fn database_from(programs: Vec<Program>, hashes: HashMap<RomHash, usize>) -> Database {
         Database {
             programs,
             hashes,
+            ..Default::default()
+        }
+    }
+
+    /// Build a database with a program for each of `roms`, named by its title and holding a single
+    /// ROM with the lowercase title as its file name, such as `known.ch8` for "Known".
+    fn database_of(roms: &[(&str, &[u8])]) -> Database {
+        let mut programs = Vec::new();
+        let mut hashes = HashMap::new();
+
+        for (index, (title, rom)) in roms.iter().enumerate() {
+            let hash = RomHash::from_rom(rom);
+            let rom = Rom {
+                file_name: Some(format!("{}.ch8", title.to_lowercase())),
+                ..Default::default()
+            };
+
+            programs.push(Program {
+                title: (*title).to_owned(),
+                roms: HashMap::from([(hash, rom)]),
+                ..Default::default()
+            });
+            hashes.insert(hash, index);
+        }
+
+        database_from(programs, hashes)
+    }
+
+    /// A temporary directory of ROM files, which is removed when dropped so a failing test doesn't
+    /// leave it behind.
+    struct RomDir(std::path::PathBuf);
+
+    impl RomDir {
+        /// Create an empty directory, unique to this test run and `name`.
+        fn new(name: &str) -> io::Result<Self> {
+            let path = std::env::temp_dir().join(format!("chip8_db-{name}-{}", std::process::id()));
+            let _ = std::fs::remove_dir_all(&path);
+            std::fs::create_dir_all(&path)?;
+
+            Ok(RomDir(path))
+        }
+
+        /// The path of `name` inside this directory.
+        fn join(&self, name: &str) -> std::path::PathBuf {
+            self.0.join(name)
+        }
+
+        /// Write a file into this directory.
+        fn write(&self, name: &str, contents: impl AsRef<[u8]>) -> io::Result<()> {
+            std::fs::write(self.join(name), contents)
+        }
+
+        /// Create a symbolic link `name` to `target`, both inside this directory.
+        #[cfg(unix)]
+        fn symlink(&self, target: &str, name: &str) -> io::Result<()> {
+            std::os::unix::fs::symlink(self.join(target), self.join(name))
+        }
+
+        /// Create a symbolic link `name` to a file that doesn't exist.
+        #[cfg(unix)]
+        fn broken_link(&self, name: &str) -> io::Result<()> {
+            self.symlink("missing.bin", name)
+        }
+    }
+
+    impl AsRef<Path> for RomDir {
+        fn as_ref(&self) -> &Path {
+            &self.0
+        }
+    }
+
+    impl Drop for RomDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    mod loading {
+        use super::*;
+
+        const PROGRAMS: &str = r##"[
+            {
+                "title": "Runtime Program",
+                "roms": {
+                    "0123456789abcdef0123456789abcdef01234567": {
+                        "platforms": ["originalChip8"]
+                    }
+                }
+            }
+        ]"##;
+
+        const HASHES: &str = r##"{
+            "0123456789abcdef0123456789abcdef01234567": 0
+        }"##;
+
+        #[test]
+        fn metadata_from_reader() -> std::io::Result<()> {
+            let db = Database::from_readers(PROGRAMS.as_bytes(), HASHES.as_bytes()).unwrap();
+            let rom = [0x00, 0xe0, 0x12, 0x00];
+            let metadata = db.get_metadata_from_reader(&rom[..])?;
+
+            assert_eq!(RomHash::from_rom(&rom), metadata.hash);
+            assert!(metadata.program.is_none());
+
+            Ok(())
+        }
+
+        #[test]
+        fn metadata_from_missing_path() {
+            let db = Database::from_readers(PROGRAMS.as_bytes(), HASHES.as_bytes()).unwrap();
+            let error = db.get_metadata_from_path("does-not-exist.ch8").unwrap_err();
+
+            assert_eq!(ErrorKind::NotFound, error.kind());
+        }
+
+        #[test]
+        fn from_readers() -> Result<(), Error> {
+            let db = Database::from_readers(PROGRAMS.as_bytes(), HASHES.as_bytes())?;
+            let hash = "0123456789abcdef0123456789abcdef01234567".parse().unwrap();
+            let metadata = db.get_metadata_from_hash(hash);
+
+            assert_eq!("Runtime Program", metadata.program.unwrap().title);
+            assert!(metadata.rom.is_some());
+
+            Ok(())
+        }
+
+        #[test]
+        fn from_readers_invalid_json() {
+            let result = Database::from_readers("[{}]".as_bytes(), HASHES.as_bytes());
+
+            assert!(matches!(
+                result,
+                Err(Error::Json {
+                    file: "programs.json",
+                    ..
+                })
+            ));
+        }
+
+        #[test]
+        fn from_readers_invalid_index() {
+            let hashes = r##"{ "0123456789abcdef0123456789abcdef01234567": 1 }"##;
+            let result = Database::from_readers(PROGRAMS.as_bytes(), hashes.as_bytes());
+
+            assert!(matches!(result, Err(Error::InvalidIndex { index: 1, .. })));
+        }
+
+        #[test]
+        fn from_dir_missing() {
+            let result = Database::from_dir("./this/directory/does/not/exist");
+
+            assert!(matches!(result, Err(Error::MissingFile(_))));
+        }
+
+        #[test]
+        fn try_new() {
+            assert!(Database::try_new().is_ok());
+        }
+    }
+
+    mod batch {
+        use super::*;
+
+        use std::path::PathBuf;
+
+        const KNOWN: &[u8] = &[0x00, 0xe0, 0x12, 0x00];
+
+        /// A database that recognizes [KNOWN] as `known.ch8`.
+        fn known_database() -> Database {
+            database_of(&[("Known", KNOWN)])
+        }
+
+        #[test]
+        fn identify_dir() -> std::io::Result<()> {
+            let dir = RomDir::new("batch")?;
+            dir.write("known.ch8", KNOWN)?;
+            dir.write("RENAMED.CH8", KNOWN)?;
+            dir.write("unknown.xo8", [0x12, 0x00])?;
+            dir.write("readme.txt", "not a ROM")?;
+
+            let report = known_database().identify_dir(&dir)?;
+
+            let names: Vec<_> = report
+                .results
+                .iter()
+                .map(|(path, _)| path.file_name().unwrap().to_str().unwrap())
+                .collect();
+
+            assert_eq!(vec!["RENAMED.CH8", "known.ch8", "unknown.xo8"], names);
+            assert_eq!(2, report.summary.matched);
+            assert_eq!(1, report.summary.unknown);
+            assert_eq!(1, report.summary.renamed.len());
+            assert_eq!("known.ch8", report.summary.renamed[0].expected);
+            assert!(report.summary.failed.is_empty());
+
+            Ok(())
+        }
+
+        #[cfg(unix)]
+        #[test]
+        fn identify_dir_symlinks() -> std::io::Result<()> {
+            let dir = RomDir::new("links")?;
+            std::fs::create_dir_all(dir.join("roms"))?;
+            dir.write("roms/known.bin", KNOWN)?;
+            dir.symlink("roms/known.bin", "known.ch8")?;
+            dir.broken_link("broken.ch8")?;
+            dir.symlink("roms", "folder.ch8")?;
+
+            let report = known_database().identify_dir(&dir)?;
+
+            assert_eq!(1, report.results.len());
+            assert_eq!(dir.join("known.ch8"), report.results[0].0);
+            assert_eq!(1, report.summary.matched);
+            assert!(report.summary.renamed.is_empty());
+            assert_eq!(1, report.summary.failed.len());
+            assert_eq!(dir.join("broken.ch8"), report.summary.failed[0].0);
+
+            Ok(())
+        }
+
+        #[test]
+        fn identify_missing_paths() {
+            let report = known_database().identify_paths([PathBuf::from("does-not-exist.ch8")]);
+
+            assert!(report.results.is_empty());
+            assert_eq!(1, report.summary.failed.len());
+        }
+    }
+
+    mod fingerprint {
+        use super::*;
+
+        use crate::fingerprint::{Fingerprint, FingerprintIndex};
+
+        /// Generate a ROM full of noise, so chunk boundaries don't line up by accident.
+        fn noise(seed: u32, len: usize) -> Vec<u8> {
+            let mut state = seed;
+
+            (0..len)
+                .map(|_| {
+                    state ^= state << 13;
+                    state ^= state >> 17;
+                    state ^= state << 5;
+                    state as u8
+                })
+                .collect()
+        }
+
+        #[test]
+        fn closest_matches() {
+            let original = noise(1, 1024);
+            let other = noise(2, 900);
+            let db = database_of(&[("Original", &original), ("Other", &other)]);
+
+            let mut index = FingerprintIndex::new();
+            assert!(index.insert(&db, &original));
+            assert!(index.insert(&db, &other));
+            assert!(!index.insert(&db, &noise(3, 100)));
+
+            let mut patched = original.clone();
+            patched[500] ^= 0xff;
+            patched.extend_from_slice(&[0x12, 0x00]);
+
+            let candidates = db.closest_matches(&index, &patched, 5);
+
+            assert_eq!(2, candidates.len());
+            assert_eq!("Original", candidates[0].program.title);
+            assert!(candidates[0].score > 0.8);
+            assert!(candidates[1].score < candidates[0].score);
+
+            let exact = db.closest_matches(&index, &other, 5);
+
+            assert_eq!(1, exact.len());
+            assert_eq!("Other", exact[0].program.title);
+            assert_eq!(1.0, exact[0].score);
+            assert!(db.closest_matches(&index, &other, 0).is_empty());
+            assert!(db.closest_matches(&index, &patched, 0).is_empty());
+        }
+
+        #[cfg(unix)]
+        #[test]
+        fn from_dir_skips_broken_entries() -> std::io::Result<()> {
+            let rom = noise(6, 256);
+            let db = database_of(&[("Found", &rom)]);
+
+            let dir = RomDir::new("fingerprint")?;
+            dir.write("found.ch8", &rom)?;
+            dir.broken_link("broken.ch8")?;
+
+            let (index, failed) = FingerprintIndex::from_dir(&db, &dir)?;
+
+            let mut expected = FingerprintIndex::new();
+            expected.insert(&db, &rom);
+
+            assert_eq!(expected, index);
+            assert_eq!(1, failed.len());
+            assert_eq!(dir.join("broken.ch8"), failed[0].0);
+
+            Ok(())
+        }
+
+        #[test]
+        fn similarity() {
+            let rom = noise(4, 512);
+            let same = Fingerprint::from_rom(&rom).similarity(&Fingerprint::from_rom(&rom));
+            let empty = Fingerprint::from_rom(&[]).similarity(&Fingerprint::from_rom(&[]));
+
+            assert!((1.0 - same).abs() < 1e-9, "{same}");
+            assert!((1.0 - empty).abs() < 1e-9, "{empty}");
+        }
+
+        #[test]
+        fn round_trip() -> Result<(), Error> {
+            let rom = noise(5, 256);
+            let db = database_of(&[("Saved", &rom)]);
+            let mut index = FingerprintIndex::new();
+            index.insert(&db, &rom);
+
+            let mut saved = Vec::new();
+            index.to_writer(&mut saved)?;
+
+            assert_eq!(index, FingerprintIndex::from_reader(&saved[..])?);
+            assert!(matches!(
+                FingerprintIndex::from_reader(&b"[]"[..]),
+                Err(Error::Json { .. })
+            ));
+
+            Ok(())
+        }
+    }
+
+    #[cfg(feature = "alt-hashes")]
+    mod digest {
+        use super::*;
+
+        use crate::digest::{DigestIndex, HashAlgorithm};
+
+        const ROM: &[u8] = b"The quick brown fox jumps over the lazy dog";
+
+        fn indexed() -> (Database, DigestIndex) {
+            let db = database_of(&[("Fox", ROM)]);
+
+            let mut index = DigestIndex::new();
+            assert!(index.insert(&db, ROM));
+            assert!(!index.insert(&db, b"unknown"));
+
+            (db, index)
+        }
+
+        #[test]
+        fn lookup_by_algorithm() {
+            let (db, index) = indexed();
+            let digests = [
+                (HashAlgorithm::Crc32, "414FA339"),
+                (HashAlgorithm::Md5, "9e107d9d372bb6826bd81d3542a419d6"),
+                (
+                    HashAlgorithm::Sha1,
+                    "2fd4e1c67a2d28fced849ee1bb76e7391b93eb12",
+                ),
+                (
+                    HashAlgorithm::Sha256,
+                    "d7a8fbb307d7809469ca9abcb0082e4f8d5651e46d3cdb762d02d0bf37c9e592",
+                ),
+            ];
+
+            for (algorithm, digest) in digests {
+                let metadata = db.lookup_digest(&index, algorithm, digest).unwrap();
+
+                assert_eq!("Fox", metadata.program.unwrap().title, "{algorithm}");
+                assert_eq!(algorithm.digest_len(), algorithm.digest(ROM).len());
+            }
+
+            assert!(db
+                .lookup_digest(
+                    &index,
+                    HashAlgorithm::Md5,
+                    "00000000000000000000000000000000"
+                )
+                .is_none());
+            assert!(db
+                .lookup_digest(&index, HashAlgorithm::Crc32, "not hex")
+                .is_none());
+        }
+
+        #[test]
+        fn lookup_unknown_sha1() {
+            let (db, index) = indexed();
+            let unknown = "0123456789abcdef0123456789abcdef01234567";
+
+            assert!(index.find_hex(HashAlgorithm::Sha1, unknown).is_none());
+            assert!(db
+                .lookup_digest(&index, HashAlgorithm::Sha1, unknown)
+                .is_none());
+        }
+
+        #[cfg(unix)]
+        #[test]
+        fn from_dir_skips_broken_entries() -> std::io::Result<()> {
+            let (db, expected) = indexed();
+
+            let dir = RomDir::new("digest")?;
+            dir.write("fox.ch8", ROM)?;
+            dir.broken_link("broken.ch8")?;
+
+            let (index, failed) = DigestIndex::from_dir(&db, &dir)?;
+
+            assert_eq!(expected, index);
+            assert_eq!(1, failed.len());
+            assert_eq!(dir.join("broken.ch8"), failed[0].0);
+
+            Ok(())
+        }
+
+        #[test]
+        fn round_trip() -> Result<(), Error> {
+            let (_, index) = indexed();
+
+            let mut saved = Vec::new();
+            index.to_writer(&mut saved)?;
+            let loaded = DigestIndex::from_reader(&saved[..])?;
+
+            assert_eq!(index, loaded);
+            assert_eq!(1, loaded.len());
+            assert!(loaded.find_hex(HashAlgorithm::Crc32, "414fa339").is_some());
+
+            Ok(())
+        }
+    }
+
+    mod filter {
+        use super::*;
+
+        use crate::{
+            input::Keymap,
+            origin::{Origin, OriginType},
+            platform::Platform,
+            quirk::{Quirk, QuirkSet},
+            release::ReleaseDate,
+        };
+
+        fn sample_database() -> Database {
+            let jam = Program {
+                title: "Jam Game".to_owned(),
+                release: Some("2021-10".to_owned()),
+                origin: Some(Origin {
+                    origin_type: Some(OriginType::GameJam),
+                    reference: Some("Octojam 8".to_owned()),
+                }),
+                authors: Some(vec!["Someone".to_owned()]),
+                roms: HashMap::from([(
+                    RomHash::from_rom(b"jam"),
+                    Rom {
+                        platforms: vec![Platform::XOChip],
+                        keys: Some(HashMap::from([(Keymap::P1Up, 5), (Keymap::P2Up, 8)])),
+                        ..Default::default()
+                    },
+                )]),
+                ..Default::default()
+            };
+            let classic = Program {
+                title: "Classic".to_owned(),
+                release: Some("1978".to_owned()),
+                roms: HashMap::from([
+                    (
+                        RomHash::from_rom(b"classic"),
+                        Rom {
+                            platforms: vec![Platform::OriginalChip8],
+                            keys: Some(HashMap::from([(Keymap::P1Up, 5)])),
+                            ..Default::default()
+                        },
+                    ),
+                    (
+                        RomHash::from_rom(b"port"),
+                        Rom {
+                            release: Some("1991".to_owned()),
+                            platforms: vec![Platform::Superchip],
+                            authors: Some(vec!["SOMEONE".to_owned()]),
+                            quirky_platforms: Some(HashMap::from([(
+                                Platform::Superchip,
+                                QuirkSet::from([(Quirk::Shift, false)]),
+                            )])),
+                            ..Default::default()
+                        },
+                    ),
+                ]),
+                ..Default::default()
+            };
+
+            database_from(vec![jam, classic], HashMap::new())
+        }
+
+        fn files(filter: crate::filter::Filter) -> Vec<RomHash> {
+            filter.into_iter().map(|(_, hash, _)| hash).collect()
+        }
+
+        #[test]
+        fn combined() {
+            let db = sample_database();
+
+            assert_eq!(
+                vec![RomHash::from_rom(b"jam")],
+                files(
+                    db.filter()
+                        .platform(Platform::XOChip)
+                        .two_players()
+                        .released_since(ReleaseDate::Year(2021))
+                )
+            );
+            assert!(files(
+                db.filter()
+                    .platform(Platform::XOChip)
+                    .released_before(ReleaseDate::Year(2021))
+            )
+            .is_empty());
+            assert_eq!(
+                vec![RomHash::from_rom(b"jam")],
+                files(db.filter().origin_type(OriginType::GameJam))
+            );
+        }
+
+        #[test]
+        fn single_conditions() {
+            let db = sample_database();
+
+            assert_eq!(3, db.filter().iter().count());
+            assert_eq!(2, files(db.filter().author("someone")).len());
+            assert_eq!(
+                vec![RomHash::from_rom(b"port")],
+                files(
+                    db.filter()
+                        .released_since(ReleaseDate::Decade(1990))
+                        .released_before(ReleaseDate::Year(2000))
+                )
+            );
+            assert_eq!(
+                vec![RomHash::from_rom(b"port")],
+                files(db.filter().quirk(Quirk::Shift, false))
+            );
+            assert!(files(db.filter().quirk(Quirk::Shift, true)).is_empty());
+            assert!(files(db.filter().font_style(crate::font::FontStyle::Octo)).is_empty());
+        }
+    }
+
+    mod binding {
+        use super::*;
+
+        use crate::{
+            binding::{hex_pad_key, Conflict, GamepadButton, HostInput, Key, Layout},
+            input::Keymap,
+        };
+
+        #[test]
+        fn hex_pad() {
+            let bindings = Layout::default().resolve(None);
+
+            assert_eq!(Some(0x0), bindings.get(HostInput::Key(Key::X)));
+            assert_eq!(Some(0xC), bindings.get(HostInput::Key(Key::Digit4)));
+            assert_eq!(Some(0xF), bindings.get(HostInput::Key(Key::V)));
+            assert_eq!(None, bindings.get(HostInput::Key(Key::Up)));
+            assert_eq!(16 + 6, bindings.keys.len());
+            assert!(bindings.conflicts.is_empty());
+        }
+
+        #[test]
+        fn controls_and_p2_fallback() {
+            let keys = HashMap::from([(Keymap::P1Up, 0x5), (Keymap::P1Down, 0x8)]);
+            let bindings = Layout::default().resolve(Some(&keys));
+            let p2_up = HostInput::Gamepad {
+                player: 2,
+                button: GamepadButton::DPadUp,
+            };
+
+            assert_eq!(Some(0x5), bindings.get(HostInput::Key(Key::Up)));
+            assert_eq!(
+                Some(0x8),
+                bindings.get(HostInput::Gamepad {
+                    player: 1,
+                    button: GamepadButton::DPadDown,
+                })
+            );
+            assert_eq!(Some(hex_pad_key(Keymap::P2Up)), bindings.get(p2_up));
+            assert!(bindings.unmapped.contains(&Keymap::P2Up));
+            assert!(!bindings.unmapped.contains(&Keymap::P1Up));
+            assert_eq!(None, bindings.get(HostInput::Key(Key::Space)));
+
+            // Player 2 can still use the raw keypad
+            assert_eq!(Some(0xC), bindings.get(HostInput::Key(Key::Digit4)));
+        }
+
+        #[test]
+        fn p2_falls_back_to_hex_pad() {
+            let keys = HashMap::from([
+                (Keymap::P1Up, 0x5),
+                (Keymap::P1Down, 0x8),
+                (Keymap::P1Left, 0x7),
+                (Keymap::P1Right, 0x9),
+                (Keymap::P1A, 0x6),
+                (Keymap::P1B, 0x1),
+            ]);
+            let bindings = Layout::default().resolve(Some(&keys));
+            let buttons = [
+                (GamepadButton::DPadUp, 0x2),
+                (GamepadButton::DPadDown, 0x8),
+                (GamepadButton::DPadLeft, 0x4),
+                (GamepadButton::DPadRight, 0x6),
+                (GamepadButton::South, 0x5),
+                (GamepadButton::East, 0x0),
+            ];
+
+            for (button, key) in buttons {
+                let player1 = HostInput::Gamepad { player: 1, button };
+                let player2 = HostInput::Gamepad { player: 2, button };
+
+                assert_eq!(Some(key), bindings.get(player2), "{button:?}");
+                assert_ne!(None, bindings.get(player1), "{button:?}");
+            }
+
+            assert!(bindings.unmapped.iter().all(|control| control.is_player2()));
+            assert_eq!(6, bindings.unmapped.len());
+            assert!(bindings.conflicts.is_empty());
+        }
+
+        #[test]
+        fn keys_beyond_first_keypad() {
+            let keys = HashMap::from([(Keymap::P1A, 0x1C), (Keymap::P2A, 0x25)]);
+            let bindings = Layout::default().resolve(Some(&keys));
+
+            assert_eq!(Some(0x1C), bindings.get(HostInput::Key(Key::Space)));
+            assert_eq!(
+                Some(0x25),
+                bindings.get(HostInput::Gamepad {
+                    player: 2,
+                    button: GamepadButton::South,
+                })
+            );
+        }
+
+        #[test]
+        fn conflicts() {
+            let layout = Layout {
+                controls: vec![
+                    (HostInput::Key(Key::Space), Keymap::P1A),
+                    (HostInput::Key(Key::Space), Keymap::P2A),
+                    (HostInput::Key(Key::W), Keymap::P1Up),
+                ],
+                ..Default::default()
+            };
+            let keys = HashMap::from([(Keymap::P1A, 0x6), (Keymap::P2A, 0xB), (Keymap::P1Up, 0x2)]);
+            let bindings = layout.resolve(Some(&keys));
+
+            assert_eq!(Some(0x6), bindings.get(HostInput::Key(Key::Space)));
+            assert_eq!(Some(0x2), bindings.get(HostInput::Key(Key::W)));
+            assert_eq!(
+                vec![
+                    Conflict {
+                        input: HostInput::Key(Key::Space),
+                        kept: 0x6,
+                        dropped: 0xB,
+                    },
+                    Conflict {
+                        input: HostInput::Key(Key::W),
+                        kept: 0x2,
+                        dropped: 0x5,
+                    },
+                ],
+                bindings.conflicts
+            );
+        }
+    }
+
+    mod color {
+        use crate::color::{Colors, Rgb};
+
+        use std::io::Result;
+
+        #[test]
+        fn parse_notations() {
+            assert_eq!(Ok(Rgb::new(0xff, 0xcc, 0x00)), "#fc0".parse());
+            assert_eq!(Ok(Rgb::new(0x99, 0x66, 0x00)), "#996600".parse());
+            assert_eq!(Ok(Rgb::new(0x99, 0x66, 0x00)), "#996600FF".parse());
+            assert_eq!(Ok(Rgb::new(0xab, 0xcd, 0xef)), "ABCDEF".parse());
+
+            for input in ["", "#", "#12", "#1234", "#12345g", "#+12345", "red"] {
+                assert!(input.parse::<Rgb>().is_err(), "{input}");
+            }
+        }
+
+        #[test]
+        fn conversions() {
+            let color = Rgb::from_u32(0x123456);
+
+            assert_eq!([0x12, 0x34, 0x56], color.to_array());
+            assert_eq!(0x123456, color.to_u32());
+            assert_eq!(color, Rgb::from([0x12, 0x34, 0x56]));
+            assert_eq!("#123456", color.to_string());
+        }
+
+        #[test]
+        fn palette_defaults() {
+            let colors = Colors {
+                pixels: Some(vec!["#000".to_owned(), "not a color".to_owned()]),
+                buzzer: Some("#fff".to_owned()),
+                silence: None,
+            };
+            let palette = colors.palette(2);
+
+            assert_eq!(
+                vec![
+                    Rgb::new(0, 0, 0),
+                    Colors::DEFAULT_PIXELS[1],
+                    Colors::DEFAULT_PIXELS[2],
+                    Colors::DEFAULT_PIXELS[3],
+                ],
+                palette.pixels
+            );
+            assert_eq!(Rgb::new(0xff, 0xff, 0xff), palette.buzzer);
+            assert_eq!(Colors::DEFAULT_SILENCE, palette.silence);
+
+            assert_eq!(
+                Colors::DEFAULT_PIXELS[..2],
+                Colors::default().palette(1).pixels
+            );
+        }
+
+        #[test]
+        fn palette_grayscale() {
+            let palette = Colors::default().palette(8);
+
+            assert_eq!(256, palette.pixels.len());
+            assert_eq!(Rgb::new(0, 0, 0), palette.pixels[0]);
+            assert_eq!(Rgb::new(0xff, 0xff, 0xff), palette.pixels[255]);
+            assert_eq!(16, Colors::default().palette(4).pixels.len());
+            assert_eq!(256, Colors::default().palette(9).pixels.len());
+        }
+
+        #[test]
+        fn round_trip() -> Result<()> {
+            let color: Rgb = serde_json::from_str(r##""#FFCC00""##)?;
+
+            assert_eq!(r##""#ffcc00""##, serde_json::to_string(&color)?);
+
+            Ok(())
+        }
+    }
+
+    mod font {
+        use crate::font::{FontStyle, LARGE_FONT_ADDRESS, SMALL_FONT_ADDRESS};
+
+        #[test]
+        fn glyphs() {
+            let zero = [0xF0, 0x90, 0x90, 0x90, 0xF0];
+
+            assert_eq!(zero, FontStyle::VIP.small_glyphs()[..5]);
+            assert_eq!(zero, FontStyle::SCHIP.small_glyphs()[..5]);
+            assert_eq!(160, FontStyle::Octo.large_glyphs().unwrap().len());
+            assert_eq!(100, FontStyle::SCHIP.large_glyphs().unwrap().len());
+            assert!(FontStyle::VIP.large_glyphs().is_none());
+        }
+
+        #[test]
+        fn fish_and_akouz1() {
+            let fish = FontStyle::Fish;
+            let akouz1 = FontStyle::Akouz1;
+
+            assert_eq!([0x60, 0xA0, 0xA0, 0xA0, 0xC0], fish.small_glyphs()[..5]);
+            assert_eq!([0xE0, 0x80, 0xC0, 0x80, 0x80], fish.small_glyphs()[75..]);
+            assert_eq!(
+                [0x7C, 0xC6, 0xCE, 0xDE, 0xD6, 0xF6, 0xE6, 0xC6, 0x7C, 0x00],
+                fish.large_glyphs().unwrap()[..10]
+            );
+            assert_eq!(160, fish.large_glyphs().unwrap().len());
+
+            assert_eq!([0x60, 0x90, 0x90, 0x90, 0x60], akouz1.small_glyphs()[..5]);
+            assert_eq!([0xF0, 0x80, 0xF0, 0x80, 0x80], akouz1.small_glyphs()[75..]);
+            assert_eq!(
+                FontStyle::SCHIP.large_glyphs().unwrap(),
+                &akouz1.large_glyphs().unwrap()[..100]
+            );
+            assert_eq!(
+                FontStyle::Octo.large_glyphs().unwrap()[100..],
+                akouz1.large_glyphs().unwrap()[100..]
+            );
+        }
+
+        #[test]
+        fn write_glyphs() {
+            for style in [FontStyle::Octo, FontStyle::Fish, FontStyle::Akouz1] {
+                let mut memory = [0u8; 4096];
+                style.write_glyphs(&mut memory);
+
+                assert_eq!(
+                    style.small_glyphs()[..],
+                    memory[SMALL_FONT_ADDRESS..LARGE_FONT_ADDRESS],
+                    "{style:?}"
+                );
+                assert_eq!(
+                    style.large_glyphs().unwrap(),
+                    &memory[LARGE_FONT_ADDRESS..LARGE_FONT_ADDRESS + 160],
+                    "{style:?}"
+                );
+            }
+
+            let mut memory = [0u8; 4096];
+            FontStyle::VIP.write_glyphs(&mut memory);
+
+            assert!(memory[LARGE_FONT_ADDRESS..].iter().all(|&byte| byte == 0));
+        }
+
+        #[test]
+        fn included_glyphs() {
+            let styles = [
+                FontStyle::VIP,
+                FontStyle::Octo,
+                FontStyle::SCHIP,
+                FontStyle::Dream6800,
+                FontStyle::ETI660,
+                FontStyle::Fish,
+                FontStyle::Akouz1,
+            ];
+
+            for style in styles {
+                let small = style.small_glyphs();
+
+                // Every glyph is at most 4 pixels wide, and no two digits look the same
+                let glyphs: Vec<_> = small.chunks(5).collect();
+                assert!(small.iter().all(|byte| byte & 0x0F == 0));
+                assert!(
+                    (1..16).all(|i| !glyphs[..i].contains(&glyphs[i])),
+                    "{style:?}"
+                );
+            }
+        }
+    }
+
+    mod release {
+        use super::*;
+
+        use crate::release::ReleaseDate;
+
+        use std::io::Result;
+
+        #[test]
+        fn parse_precisions() {
+            let dates = [
+                ("19xx", ReleaseDate::Century(1900)),
+                ("199x", ReleaseDate::Decade(1990)),
+                ("1978", ReleaseDate::Year(1978)),
+                (
+                    "1990-05",
+                    ReleaseDate::Month {
+                        year: 1990,
+                        month: 5,
+                    },
+                ),
+                (
+                    "2024-02-29",
+                    ReleaseDate::Day {
+                        year: 2024,
+                        month: 2,
+                        day: 29,
+                    },
+                ),
+            ];
+
+            for (input, date) in dates {
+                assert_eq!(Ok(date), input.parse());
+                assert_eq!(input, date.to_string());
+            }
+        }
+
+        #[test]
+        fn parse_invalid() {
+            for input in [
+                "",
+                "78",
+                "1990-5",
+                "1990-13",
+                "2023-02-29",
+                "1990-05-01-01",
+                "19x0",
+                "soon",
+            ] {
+                assert!(input.parse::<ReleaseDate>().is_err(), "{input}");
+            }
+        }
+
+        #[test]
+        fn ordering() {
+            let century: ReleaseDate = "19xx".parse().unwrap();
+            let year: ReleaseDate = "1990".parse().unwrap();
+            let month: ReleaseDate = "1990-01".parse().unwrap();
+            let day: ReleaseDate = "1990-01-01".parse().unwrap();
+
+            assert!(century < year && year < month && month < day);
+            assert!(day < "1990-02".parse().unwrap());
+            assert!("199x".parse::<ReleaseDate>().unwrap() < "1991".parse().unwrap());
+        }
+
+        #[test]
+        fn accessors() {
+            let rom = Rom {
+                release: Some("sometime".to_owned()),
+                ..Default::default()
+            };
+            let program = Program {
+                release: Some("2023-06-24".to_owned()),
+                ..Default::default()
+            };
+
+            assert_eq!(None, rom.release_date());
+            assert_eq!(None, Rom::default().release_date());
+            assert_eq!(Some(2023), program.release_date().map(|date| date.year()));
+        }
+
+        #[test]
+        fn round_trip() -> Result<()> {
+            let date: ReleaseDate = serde_json::from_str(r##""1990-05""##)?;
+
+            assert_eq!(r##""1990-05""##, serde_json::to_string(&date)?);
+            assert!(serde_json::from_str::<ReleaseDate>(r##""May 1990""##).is_err());
+
+            Ok(())
+        }
+    }
+
+    mod rotation {
+        use crate::rotation::ScreenRotation;
+
+        const ROTATIONS: [ScreenRotation; 4] = [
+            ScreenRotation::Landscape,
+            ScreenRotation::Portrait,
+            ScreenRotation::LandscapeFlipped,
+            ScreenRotation::PortraitFlipped,
+        ];
+
+        /// A 3x2 framebuffer with a different value in every pixel.
+        const FRAMEBUFFER: [u8; 6] = [1, 2, 3, 4, 5, 6];
+
+        fn rotated(rotation: ScreenRotation) -> Vec<u8> {
+            let mut output = [0; 6];
+            rotation.rotate(&FRAMEBUFFER, 3, 2, &mut output);
+            output.to_vec()
+        }
+
+        #[test]
+        fn rotate() {
+            assert_eq!(vec![1, 2, 3, 4, 5, 6], rotated(ScreenRotation::Landscape));
+            assert_eq!(vec![4, 1, 5, 2, 6, 3], rotated(ScreenRotation::Portrait));
+            assert_eq!(
+                vec![6, 5, 4, 3, 2, 1],
+                rotated(ScreenRotation::LandscapeFlipped)
+            );
+            assert_eq!(
+                vec![3, 6, 2, 5, 1, 4],
+                rotated(ScreenRotation::PortraitFlipped)
+            );
+        }
+
+        #[test]
+        fn pointer_coordinates() {
+            for rotation in ROTATIONS {
+                let (width, height) = rotation.rotated_size(3, 2);
+                let output = rotated(rotation);
+
+                for y in 0..height {
+                    for x in 0..width {
+                        let (cx, cy) = rotation.to_chip8(x, y, 3, 2).unwrap();
+
+                        assert_eq!(FRAMEBUFFER[cy * 3 + cx], output[y * width + x]);
+                        assert_eq!(Some((x, y)), rotation.to_rotated(cx, cy, 3, 2));
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn coordinates_outside_display() {
+            for rotation in ROTATIONS {
+                let (width, height) = rotation.rotated_size(3, 2);
+
+                assert_eq!(None, rotation.to_chip8(width, 0, 3, 2));
+                assert_eq!(None, rotation.to_chip8(0, height, 3, 2));
+                assert_eq!(None, rotation.to_chip8(usize::MAX, usize::MAX, 3, 2));
+                assert_eq!(None, rotation.to_rotated(3, 0, 3, 2));
+                assert_eq!(None, rotation.to_rotated(0, 2, 3, 2));
+                assert_eq!(None, rotation.to_chip8(0, 0, 0, 0));
+            }
+        }
+
+        #[test]
+        fn rotated_size() {
+            assert_eq!(
+                (64, 32),
+                ScreenRotation::LandscapeFlipped.rotated_size(64, 32)
+            );
+            assert_eq!((32, 64), ScreenRotation::Portrait.rotated_size(64, 32));
+        }
+
+        #[test]
+        #[should_panic]
+        fn rotate_wrong_size() {
+            ScreenRotation::Portrait.rotate(&FRAMEBUFFER, 3, 2, &mut [0; 5]);
+        }
+    }
+
+    mod search {
+        use super::*;
+
+        use crate::search::Field;
+
+        fn program(title: &str, description: &str, author: &str) -> Program {
+            Program {
+                title: title.to_owned(),
+                description: Some(description.to_owned()),
+                authors: Some(vec![author.to_owned()]),
+                ..Default::default()
+            }
+        }
+
+        fn titles(db: &Database, query: &str) -> Vec<String> {
+            db.search_index()
+                .search(query)
+                .into_iter()
+                .map(|hit| hit.program.title.clone())
+                .collect()
+        }
+
+        fn sample_database() -> Database {
+            database_from(
+                vec![
+                    program("Pong", "Bounce a ball past your opponent.", "Paul Vervalin"),
+                    program(
+                        "Space Invaders",
+                        "Shoot the invaders from space.",
+                        "David Winter",
+                    ),
+                    program(
+                        "Breakout",
+                        "A Pong-like game where you break bricks.",
+                        "Space Cadet",
+                    ),
+                ],
+                HashMap::new(),
+            )
+        }
+
+        #[test]
+        fn title_above_description() {
+            let db = sample_database();
+
+            assert_eq!(vec!["Pong", "Breakout"], titles(&db, "pong"));
+            assert_eq!(vec!["Space Invaders", "Breakout"], titles(&db, "SPACE"));
+        }
+
+        #[test]
+        fn every_word_must_match() {
+            let db = sample_database();
+
+            assert_eq!(vec!["Space Invaders"], titles(&db, "space inv"));
+            assert!(titles(&db, "pong invaders").is_empty());
+            assert!(titles(&db, "").is_empty());
+            assert!(titles(&db, "  ...  ").is_empty());
+        }
+
+        #[test]
+        fn prefix_and_author() {
+            let db = sample_database();
+            let hits = db.search_index().search("win");
+
+            assert_eq!(1, hits.len());
+            assert_eq!(1, hits[0].index);
+            assert_eq!(Field::Author.weight(), hits[0].score);
+
+            assert!(
+                db.search_index().search("winter")[0].score
+                    > db.search_index().search("wint")[0].score
+            );
+        }
+
+        #[test]
+        fn embedded_title() {
+            let mut db = sample_database();
+            let hash = RomHash::from_rom(b"breakout");
+            db.programs[2].roms.insert(
+                hash,
+                Rom {
+                    embedded_title: Some("BRIX".to_owned()),
+                    ..Default::default()
+                },
+            );
+
+            assert_eq!(vec!["Breakout"], titles(&db, "brix"));
+        }
+
+        #[test]
+        fn title_prefix_above_exact_embedded_title() {
+            let mut db = sample_database();
+            db.programs[2].roms.insert(
+                RomHash::from_rom(b"breakout"),
+                Rom {
+                    embedded_title: Some("BRIX".to_owned()),
+                    ..Default::default()
+                },
+            );
+            db.programs
+                .push(program("Brixton", "A city builder.", "Someone"));
+
+            let hits = db.search_index().search("brix");
+
+            assert_eq!(vec!["Brixton", "Breakout"], titles(&db, "brix"));
+            assert_eq!(Field::Title.weight(), hits[0].score);
+            assert_eq!(Field::EmbeddedTitle.weight() + 1, hits[1].score);
+        }
+    }
+
+    mod shared {
+        use super::*;
+
+        #[test]
+        fn initialized_once() {
+            assert!(std::ptr::eq(database(), database()));
+        }
+
+        #[test]
+        fn borrowing_lookups() {
+            let hash = *database().hashes.keys().next().unwrap();
+            let program = get_program(hash).unwrap();
+
+            assert!(std::ptr::eq(
+                program.roms.get(&hash).unwrap(),
+                get_rom(hash).unwrap()
+            ));
+            assert!(get_program(RomHash::default()).is_none());
+            assert!(get_rom(RomHash::default()).is_none());
+        }
+
+        #[test]
+        fn lookup_borrows() {
+            let hash = *database().hashes.keys().next().unwrap();
+            let metadata = lookup(hash);
+
+            assert_eq!(hash, metadata.hash);
+            assert!(std::ptr::eq(
+                get_program(hash).unwrap(),
+                metadata.program.unwrap()
+            ));
+            assert!(std::ptr::eq(get_rom(hash).unwrap(), metadata.rom.unwrap()));
+
+            let owned = Metadata::from(metadata);
+
+            assert_eq!(metadata.program, owned.program.as_ref());
+            assert_eq!(metadata.rom, owned.rom.as_ref());
+        }
+    }
+
+    mod validation {
+        use super::*;
+
+        use crate::validation::Issue;
+
+        fn hash(digit: char) -> RomHash {
+            digit.to_string().repeat(40).parse().unwrap()
+        }
+
+        fn program(title: &str, hashes: &[RomHash]) -> Program {
+            Program {
+                title: title.to_owned(),
+                roms: hashes.iter().map(|hash| (*hash, Rom::default())).collect(),
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn embedded_is_valid() {
+            let report = Database::new().validate();
+
+            assert!(report.is_valid(), "{report}");
+        }
+
+        #[test]
+        fn reports_every_issue() {
+            let db = database_from(
+                vec![
+                    program("Valid", &[hash('a')]),
+                    program("Orphan and Duplicate", &[hash('a'), hash('b')]),
+                    program("Empty", &[]),
+                ],
+                HashMap::from([(hash('a'), 0), (hash('c'), 0), (hash('d'), 7)]),
+            );
 
-            #[cfg(feature = "extra-data")]
-            platforms,
+            let report = db.validate();
 
-            #[cfg(feature = "extra-data")]
-            quirks,
+            assert!(!report.is_valid());
+            assert_eq!(
+                vec![
+                    Issue::DanglingIndex {
+                        hash: hash('d'),
+                        index: 7
+                    },
+                    Issue::MissingRom {
+                        hash: hash('c'),
+                        index: 0
+                    },
+                    Issue::OrphanRom {
+                        hash: hash('a'),
+                        index: 1
+                    },
+                    Issue::OrphanRom {
+                        hash: hash('b'),
+                        index: 1
+                    },
+                    Issue::DuplicateHash {
+                        hash: hash('a'),
+                        indices: vec![0, 1]
+                    },
+                    Issue::EmptyProgram { index: 2 },
+                ],
+                report.issues
+            );
+
+            // Lookups must not panic even when the database is inconsistent
+            assert!(db.get_metadata_from_hash(hash('d')).program.is_none());
         }
     }
 
-    /// Lookup the metadata for a specific ROM file by hashing it.
-    pub fn get_metadata(&self, rom: &[u8]) -> Metadata {
-        let mut hasher = Sha1::new();
-        hasher.update(rom);
-        let hash = hasher.finalize();
-        let mut buf = [0u8; 40];
-        let hash = base16ct::lower::encode_str(&hash, &mut buf).unwrap();
+    mod merge {
+        use super::*;
 
-        self.get_metadata_from_hash(hash)
-    }
+        use crate::{
+            platform::Platform,
+            quirk::{Quirk, QuirkSet},
+        };
 
-    /// Lookup the metadata for a specific hash string.
-    pub fn get_metadata_from_hash(&self, hash: &str) -> Metadata {
-        let hash = hash.to_owned();
-        let program = self.hashes.get(&hash).map(|i| self.programs[*i].clone());
-        let rom = program
-            .as_ref()
-            .and_then(|prog| prog.roms.get(&hash).cloned());
+        const HASH: RomHash = RomHash([
+            0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef, 0x01, 0x23, 0x45, 0x67, 0x89, 0xab,
+            0xcd, 0xef, 0x01, 0x23, 0x45, 0x67,
+        ]);
+
+        fn base() -> Database {
+            let rom = Rom {
+                file_name: Some("base.ch8".to_owned()),
+                description: Some("Base description".to_owned()),
+                platforms: vec![Platform::OriginalChip8],
+                quirky_platforms: Some(HashMap::from([(
+                    Platform::OriginalChip8,
+                    QuirkSet::from([(Quirk::Shift, true), (Quirk::Jump, true)]),
+                )])),
+                ..Default::default()
+            };
+
+            let program = Program {
+                title: "Base".to_owned(),
+                authors: Some(vec!["Upstream".to_owned()]),
+                roms: HashMap::from([(HASH, rom)]),
+                ..Default::default()
+            };
+
+            database_from(vec![program], HashMap::from([(HASH, 0)]))
+        }
 
-        Metadata { hash, program, rom }
-    }
-}
+        #[test]
+        fn overrides_per_field() {
+            let rom = Rom {
+                description: Some("Overlay description".to_owned()),
+                quirky_platforms: Some(HashMap::from([(
+                    Platform::OriginalChip8,
+                    QuirkSet::from([(Quirk::Shift, false)]),
+                )])),
+                ..Default::default()
+            };
+
+            let overlay = Program {
+                roms: HashMap::from([(HASH, rom)]),
+                ..Default::default()
+            };
+
+            let mut db = base();
+            db.merge(database_from(vec![overlay], HashMap::new()));
+
+            assert_eq!(1, db.programs.len());
+
+            let metadata = db.get_metadata_from_hash(HASH);
+            let program = metadata.program.unwrap();
+            let rom = metadata.rom.unwrap();
+
+            assert_eq!("Base", program.title);
+            assert_eq!(vec!["Upstream"], program.authors.unwrap());
+            assert_eq!("base.ch8", rom.file_name.unwrap());
+            assert_eq!("Overlay description", rom.description.unwrap());
+            assert_eq!(vec![Platform::OriginalChip8], rom.platforms);
 
-/// Metadata results from a ROM lookup
-#[derive(Clone, Debug, Default)]
-pub struct Metadata {
-    /// During ROM lookup, this will be populated with the hash used.
-    pub hash: String,
+            let quirks = rom.quirky_platforms.unwrap()[&Platform::OriginalChip8];
 
-    /// The program matching the listed hash.
-    pub program: Option<Program>,
+            assert_eq!(Some(false), quirks.get(Quirk::Shift));
+            assert_eq!(Some(true), quirks.get(Quirk::Jump));
+        }
 
-    /// Any ROM-specific metadata, otherwise defaulting to the values in program.
-    pub rom: Option<Rom>,
-}
+        #[test]
+        fn appends_new_programs() {
+            let hash = RomHash::from_rom(b"homebrew");
+            let overlay = Program {
+                title: "Homebrew".to_owned(),
+                roms: HashMap::from([(hash, Rom::default())]),
+                ..Default::default()
+            };
+
+            let mut db = base();
+            db.merge(database_from(vec![overlay], HashMap::new()));
+
+            assert_eq!(2, db.programs.len());
+            assert!(db.validate().is_valid());
+            assert_eq!(
+                "Base",
+                db.get_metadata_from_hash(HASH).program.unwrap().title
+            );
+            assert_eq!(
+                "Homebrew",
+                db.get_metadata_from_hash(hash).program.unwrap().title
+            );
+        }
 
-#[cfg(test)]
-mod test {
-    use super::*;
+        #[test]
+        fn merges_overlay_programs_sharing_a_new_hash() {
+            let hash = RomHash::from_rom(b"homebrew");
+            let first = Program {
+                title: "Homebrew".to_owned(),
+                roms: HashMap::from([(hash, Rom::default())]),
+                ..Default::default()
+            };
+            let second = Program {
+                authors: Some(vec!["Overlay".to_owned()]),
+                roms: HashMap::from([(
+                    hash,
+                    Rom {
+                        file_name: Some("homebrew.ch8".to_owned()),
+                        ..Default::default()
+                    },
+                )]),
+                ..Default::default()
+            };
+
+            let mut db = base();
+            db.merge(database_from(vec![first, second], HashMap::new()));
+
+            assert_eq!(2, db.programs.len());
+            assert!(db.validate().is_valid());
+
+            let metadata = db.get_metadata_from_hash(hash);
+            let program = metadata.program.unwrap();
+
+            assert_eq!("Homebrew", program.title);
+            assert_eq!(vec!["Overlay"], program.authors.unwrap());
+            assert_eq!("homebrew.ch8", metadata.rom.unwrap().file_name.unwrap());
+        }
+    }
 
     mod program {
         use super::*;
@@ -285,7 +1882,8 @@ mod test {
                 program.urls.unwrap()
             );
 
-            let rom = program.roms["0123456789abcdef0123456789abcdef01234567"].clone();
+            let hash = "0123456789abcdef0123456789abcdef01234567".parse().unwrap();
+            let rom = program.roms[&hash].clone();
 
             assert_eq!("test-program.ch8", &rom.file_name.unwrap());
             assert_eq!("Test Program Embedded", &rom.embedded_title.unwrap());
@@ -296,15 +1894,15 @@ mod test {
             assert_eq!("2023-06-24", rom.release.unwrap());
             assert_eq!(vec![Platform::OriginalChip8], rom.platforms);
 
-            let quirks = rom.quirky_platforms.unwrap()[&Platform::OriginalChip8].clone();
+            let quirks = rom.quirky_platforms.unwrap()[&Platform::OriginalChip8];
 
-            assert!(quirks[&Quirk::Shift]);
-            assert!(!quirks[&Quirk::MemoryIncrementByX]);
-            assert!(quirks[&Quirk::MemoryLeaveIUnchanged]);
-            assert!(!quirks[&Quirk::Wrap]);
-            assert!(quirks[&Quirk::Jump]);
-            assert!(!quirks[&Quirk::VBlank]);
-            assert!(quirks[&Quirk::Logic]);
+            assert_eq!(Some(true), quirks.get(Quirk::Shift));
+            assert_eq!(Some(false), quirks.get(Quirk::MemoryIncrementByX));
+            assert_eq!(Some(true), quirks.get(Quirk::MemoryLeaveIUnchanged));
+            assert_eq!(Some(false), quirks.get(Quirk::Wrap));
+            assert_eq!(Some(true), quirks.get(Quirk::Jump));
+            assert_eq!(Some(false), quirks.get(Quirk::VBlank));
+            assert_eq!(Some(true), quirks.get(Quirk::Logic));
 
             assert_eq!(vec!["Someone Else"], rom.authors.unwrap());
             assert_eq!(
@@ -365,7 +1963,8 @@ mod test {
 
             assert_eq!("Minimal", program.title);
 
-            let rom = program.roms["0123456789abcdef0123456789abcdef01234567"].clone();
+            let hash = "0123456789abcdef0123456789abcdef01234567".parse().unwrap();
+            let rom = program.roms[&hash].clone();
 
             assert_eq!(vec![Platform::OriginalChip8], rom.platforms);
 
@@ -373,9 +1972,447 @@ mod test {
         }
     }
 
+    mod platform_selection {
+        use super::*;
+
+        use crate::platform::{Platform, PlatformSelection, SelectionReason};
+
+        fn rom(platforms: &[Platform]) -> Rom {
+            Rom {
+                platforms: platforms.to_vec(),
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn prefers_listed_platforms() {
+            let rom = rom(&[Platform::XOChip, Platform::Superchip]);
+
+            assert_eq!(
+                Some(PlatformSelection {
+                    platform: Platform::XOChip,
+                    reason: SelectionReason::Listed { rank: 0 },
+                }),
+                rom.select_platform(&[Platform::Superchip, Platform::XOChip])
+            );
+
+            assert_eq!(
+                Some(PlatformSelection {
+                    platform: Platform::Superchip,
+                    reason: SelectionReason::Listed { rank: 1 },
+                }),
+                rom.select_platform(&[Platform::Superchip, Platform::OriginalChip8])
+            );
+        }
+
+        #[test]
+        fn falls_back_to_closest_superset() {
+            let rom = rom(&[Platform::Chip48]);
+            let selection = rom
+                .select_platform(&[Platform::Superchip, Platform::Superchip1])
+                .unwrap();
+
+            assert_eq!(Platform::Superchip1, selection.platform);
+            assert_eq!(
+                SelectionReason::Superset {
+                    of: Platform::Chip48
+                },
+                selection.reason
+            );
+            assert_eq!(
+                "running in SUPER-CHIP 1.0 mode because it can run programs written for CHIP48, \
+                 which is listed by the ROM",
+                selection.to_string()
+            );
+        }
+
+        #[test]
+        fn unsupported() {
+            let rom = rom(&[Platform::XOChip]);
+
+            assert_eq!(None, rom.select_platform(&[Platform::OriginalChip8]));
+        }
+    }
+
+    mod quirk_set {
+        use crate::quirk::{Quirk, QuirkSet};
+
+        use std::io::Result;
+
+        #[test]
+        fn tri_state() {
+            let mut quirks = QuirkSet::new().with(Quirk::Shift, true);
+            quirks.set(Quirk::Jump, false);
+
+            assert_eq!(Some(true), quirks.get(Quirk::Shift));
+            assert_eq!(Some(false), quirks.get(Quirk::Jump));
+            assert_eq!(None, quirks.get(Quirk::Wrap));
+            assert!(!quirks.is_enabled(Quirk::Wrap));
+            assert_eq!(2, quirks.len());
+
+            quirks.remove(Quirk::Shift);
+
+            assert_eq!(None, quirks.get(Quirk::Shift));
+            assert!(!quirks.is_enabled(Quirk::Shift));
+            assert_eq!(1, quirks.len());
+        }
+
+        #[test]
+        fn iter() {
+            let quirks = QuirkSet::from([(Quirk::Logic, true), (Quirk::Shift, false)]);
+
+            assert_eq!(
+                vec![(Quirk::Shift, false), (Quirk::Logic, true)],
+                quirks.into_iter().collect::<Vec<_>>()
+            );
+            assert_eq!(0, QuirkSet::new().into_iter().count());
+        }
+
+        #[test]
+        fn round_trip() -> Result<()> {
+            let input = r##"{"shift":true,"vblank":false}"##;
+            let quirks: QuirkSet = serde_json::from_str(input)?;
+
+            assert_eq!(
+                QuirkSet::from([(Quirk::Shift, true), (Quirk::VBlank, false)]),
+                quirks
+            );
+            assert_eq!(input, serde_json::to_string(&quirks)?);
+
+            Ok(())
+        }
+    }
+
+    mod hash {
+        use crate::hash::RomHash;
+
+        use std::io::Result;
+
+        #[test]
+        fn parse_any_case() {
+            let lower: RomHash = "0123456789abcdef0123456789abcdef01234567".parse().unwrap();
+            let upper: RomHash = "0123456789ABCDEF0123456789ABCDEF01234567".parse().unwrap();
+
+            assert_eq!(lower, upper);
+            assert_eq!(
+                "0123456789abcdef0123456789abcdef01234567",
+                upper.to_string()
+            );
+        }
+
+        #[test]
+        fn parse_invalid() {
+            assert!("".parse::<RomHash>().is_err());
+            assert!("0123456789abcdef".parse::<RomHash>().is_err());
+            assert!("0123456789abcdef0123456789abcdef0123456789"
+                .parse::<RomHash>()
+                .is_err());
+            assert!("0123456789abcdef0123456789abcdef0123456g"
+                .parse::<RomHash>()
+                .is_err());
+        }
+
+        #[test]
+        fn from_rom() {
+            assert_eq!(
+                "da39a3ee5e6b4b0d3255bfef95601890afd80709",
+                RomHash::from_rom(&[]).to_string()
+            );
+        }
+
+        #[test]
+        fn from_reader() -> Result<()> {
+            let rom: Vec<u8> = (0..20_000).map(|i| i as u8).collect();
+
+            assert_eq!(RomHash::from_rom(&rom), RomHash::from_reader(&rom[..])?);
+
+            Ok(())
+        }
+
+        #[test]
+        fn round_trip() -> Result<()> {
+            let input = r##""0123456789abcdef0123456789abcdef01234567""##;
+            let hash: RomHash = serde_json::from_str(input)?;
+
+            assert_eq!(input, serde_json::to_string(&hash)?);
+            assert!(serde_json::from_str::<RomHash>(r##""0123""##).is_err());
+
+            Ok(())
+        }
+    }
+
+    mod touch {
+        use super::*;
+
+        use crate::{
+            input::{Keymap, TouchInputMode},
+            rotation::ScreenRotation,
+            touch::SwipeGesture,
+        };
+
+        fn key_at(mode: TouchInputMode, rotation: ScreenRotation, x: f32, y: f32) -> Option<u8> {
+            let keys = HashMap::from([(Keymap::P1Up, 0x5), (Keymap::P1A, 0x6)]);
+
+            mode.layout(rotation, Some(&keys))
+                .iter()
+                .find(|region| region.rect.contains(x, y))
+                .map(|region| region.key)
+        }
+
+        #[test]
+        fn seg16() {
+            let regions = TouchInputMode::Seg16.layout(ScreenRotation::Portrait, None);
+
+            assert_eq!(16, regions.len());
+            assert!(regions.iter().all(|region| !region.visible));
+            assert!(TouchInputMode::Seg16Fill
+                .layout(ScreenRotation::Portrait, None)
+                .iter()
+                .all(|region| region.visible));
+
+            assert_eq!(
+                Some(0x1),
+                key_at(TouchInputMode::Seg16, ScreenRotation::Landscape, 0.0, 0.0)
+            );
+            assert_eq!(
+                Some(0xF),
+                key_at(TouchInputMode::Seg16, ScreenRotation::Landscape, 0.99, 0.99)
+            );
+            assert_eq!(
+                Some(0xF),
+                key_at(TouchInputMode::Seg16, ScreenRotation::Landscape, 1.0, 1.0)
+            );
+            assert_eq!(
+                Some(0xC),
+                key_at(TouchInputMode::Seg16, ScreenRotation::Landscape, 1.0, 0.0)
+            );
+            assert_eq!(
+                None,
+                key_at(TouchInputMode::Seg16, ScreenRotation::Landscape, 1.01, 0.5)
+            );
+        }
+
+        #[test]
+        fn vip() {
+            let landscape = ScreenRotation::Landscape;
+            let portrait = ScreenRotation::PortraitFlipped;
+
+            assert_eq!(None, key_at(TouchInputMode::VIP, landscape, 0.25, 0.1));
+            assert_eq!(Some(0x1), key_at(TouchInputMode::VIP, landscape, 0.55, 0.1));
+            assert_eq!(None, key_at(TouchInputMode::VIP, portrait, 0.1, 0.25));
+            assert_eq!(Some(0x1), key_at(TouchInputMode::VIP, portrait, 0.1, 0.55));
+            assert_eq!(Some(0xF), key_at(TouchInputMode::VIP, landscape, 1.0, 1.0));
+            assert_eq!(Some(0xF), key_at(TouchInputMode::VIP, portrait, 1.0, 1.0));
+        }
+
+        #[test]
+        fn gamepad() {
+            let keys = HashMap::from([(Keymap::P1Up, 0x5), (Keymap::P1A, 0x6)]);
+            let regions = TouchInputMode::Gamepad.layout(ScreenRotation::Landscape, Some(&keys));
+
+            assert_eq!(2, regions.len());
+            assert_eq!(
+                Some(0x5),
+                key_at(
+                    TouchInputMode::Gamepad,
+                    ScreenRotation::Landscape,
+                    0.15,
+                    0.45
+                )
+            );
+            assert_eq!(
+                Some(0x6),
+                key_at(TouchInputMode::Gamepad, ScreenRotation::Landscape, 0.9, 0.7)
+            );
+            assert!(TouchInputMode::Gamepad
+                .layout(ScreenRotation::Landscape, None)
+                .is_empty());
+        }
+
+        #[test]
+        fn swipe() {
+            let keys = HashMap::from([(Keymap::P1Up, 0x5), (Keymap::P1A, 0x6)]);
+
+            assert!(TouchInputMode::Swipe
+                .layout(ScreenRotation::Landscape, Some(&keys))
+                .is_empty());
+            assert_eq!(Some(0x5), SwipeGesture::Up.key(Some(&keys)));
+            assert_eq!(Some(0x6), SwipeGesture::Tap.key(Some(&keys)));
+            assert_eq!(None, SwipeGesture::Left.key(Some(&keys)));
+            assert_eq!(None, SwipeGesture::Up.key(None));
+        }
+    }
+
+    #[cfg(feature = "static-data")]
+    mod static_data {
+        use super::*;
+
+        use crate::{
+            quirk::Quirk,
+            static_data::{
+                lookup, lookup_rom, PROGRAMS, ROTATIONS, VARIANTS_FONTSTYLE, VARIANTS_KEYMAP,
+                VARIANTS_ORIGINTYPE, VARIANTS_PLATFORM, VARIANTS_QUIRK, VARIANTS_TOUCHINPUTMODE,
+            },
+        };
+        use serde::{de::DeserializeOwned, Serialize};
+        use serde_json::Value;
+        use std::fmt::Debug;
+
+        /// Check that every name the build script maps to a variant is the name serde uses for it.
+        fn assert_serde_names<T, J>(variants: &[(J, T)])
+        where
+            T: Copy + Debug + DeserializeOwned + PartialEq + Serialize,
+            J: Copy + Into<Value>,
+        {
+            for &(name, variant) in variants {
+                let name = name.into();
+
+                assert_eq!(name, serde_json::to_value(variant).unwrap());
+                assert_eq!(variant, serde_json::from_value::<T>(name).unwrap());
+            }
+        }
+
+        #[test]
+        fn variants_match_serde() {
+            assert_serde_names(VARIANTS_PLATFORM);
+            assert_serde_names(VARIANTS_QUIRK);
+            assert_serde_names(VARIANTS_KEYMAP);
+            assert_serde_names(VARIANTS_ORIGINTYPE);
+            assert_serde_names(VARIANTS_TOUCHINPUTMODE);
+            assert_serde_names(VARIANTS_FONTSTYLE);
+            assert_serde_names(ROTATIONS);
+
+            for platform in Platform::ALL {
+                assert!(VARIANTS_PLATFORM.iter().any(|&(_, p)| p == platform));
+            }
+
+            for quirk in Quirk::ALL {
+                assert!(VARIANTS_QUIRK.iter().any(|&(_, q)| q == quirk));
+            }
+        }
+
+        #[test]
+        fn matches_database() {
+            let db = Database::new();
+            let programs: Vec<Program> = PROGRAMS.iter().map(Program::from).collect();
+
+            assert_eq!(db.programs, programs);
+
+            for (hash, &index) in &db.hashes {
+                let (program, rom) = lookup(*hash).unwrap();
+
+                assert_eq!(db.programs[index], Program::from(program));
+                assert_eq!(db.programs[index].roms[hash], Rom::from(rom));
+            }
+        }
+
+        #[test]
+        fn unknown() {
+            assert!(lookup(RomHash::default()).is_none());
+            assert!(lookup_rom(&[]).is_none());
+        }
+    }
+
+    mod capability {
+        use crate::{
+            capability::{AudioModel, Capabilities, Extension},
+            platform::{Platform, Resolution},
+        };
+
+        #[test]
+        fn supersets_are_capable() {
+            for platform in Platform::ALL {
+                let capabilities = platform.capabilities();
+
+                for superset in platform.supersets() {
+                    let superset = superset.capabilities();
+
+                    assert!(superset.memory_size >= capabilities.memory_size);
+                    assert!(superset.bitplanes >= capabilities.bitplanes);
+                    assert!(superset.stack_depth >= capabilities.stack_depth);
+                    assert!(capabilities
+                        .display_modes
+                        .iter()
+                        .all(|mode| superset.display_modes.contains(mode)));
+                    assert!(capabilities
+                        .extensions
+                        .iter()
+                        .all(|&extension| superset.supports(extension)));
+                }
+            }
+        }
+
+        #[test]
+        fn display_modes_are_sorted() {
+            for platform in Platform::ALL {
+                let modes = platform.capabilities().display_modes;
+
+                assert!(!modes.is_empty());
+                assert_eq!(Some(platform.lowres()), platform.capabilities().lowres());
+                assert!(modes
+                    .windows(2)
+                    .all(|pair| pair[0].pixels() < pair[1].pixels()));
+            }
+        }
+
+        #[test]
+        fn xo_chip() {
+            let capabilities = Platform::XOChip.capabilities();
+
+            assert_eq!(65536, capabilities.memory_size);
+            assert_eq!(2, capabilities.bitplanes);
+            assert_eq!(AudioModel::Pattern, capabilities.audio);
+            assert_eq!(Some(Resolution::new(64, 32)), capabilities.lowres());
+            assert_eq!(Some(Resolution::new(128, 64)), capabilities.hires());
+            assert!(capabilities.supports(Extension::LongI));
+            assert!(!capabilities.supports(Extension::MegaChip));
+        }
+
+        #[test]
+        fn original_chip8() {
+            let capabilities = Platform::OriginalChip8.capabilities();
+
+            assert_eq!(4096, capabilities.memory_size);
+            assert_eq!(12, capabilities.stack_depth);
+            assert_eq!(AudioModel::Buzzer, capabilities.audio);
+            assert_eq!(None, capabilities.hires());
+            assert!(capabilities.extensions.is_empty());
+            assert!(!Platform::Superchip1
+                .capabilities()
+                .supports(Extension::Scroll));
+        }
+
+        #[test]
+        fn without_display_modes() {
+            let capabilities = Capabilities {
+                display_modes: &[],
+                ..Platform::OriginalChip8.capabilities()
+            };
+
+            assert_eq!(None, capabilities.lowres());
+            assert_eq!(None, capabilities.hires());
+        }
+
+        #[cfg(feature = "extra-data")]
+        #[test]
+        fn matches_platform_details() {
+            for details in &crate::Database::new().platforms {
+                let capabilities = details.id.capabilities();
+                let mut resolutions = details.display_resolutions.clone();
+                resolutions.sort_by_key(Resolution::pixels);
+
+                assert_eq!(capabilities.display_modes, resolutions, "{}", details.id);
+            }
+        }
+    }
+
     #[cfg(feature = "extra-data")]
     mod platform {
-        use crate::{platform::Platform, quirk::Quirk};
+        use crate::{
+            platform::{Platform, Resolution},
+            quirk::Quirk,
+            rotation::ScreenRotation,
+        };
 
         use super::*;
 
@@ -403,16 +2440,107 @@ mod test {
 
             assert_eq!(Platform::OriginalChip8, platform.id);
             assert_eq!("Minimal Platform Example", platform.name);
-            assert_eq!(vec!["64x32"], platform.display_resolutions);
+            assert_eq!(vec![Resolution::new(64, 32)], platform.display_resolutions);
             assert_eq!(15, platform.default_tickrate);
 
-            assert!(!platform.quirks[&Quirk::Shift]);
-            assert!(!platform.quirks[&Quirk::MemoryIncrementByX]);
-            assert!(!platform.quirks[&Quirk::MemoryLeaveIUnchanged]);
-            assert!(!platform.quirks[&Quirk::Wrap]);
-            assert!(!platform.quirks[&Quirk::Jump]);
-            assert!(platform.quirks[&Quirk::VBlank]);
-            assert!(platform.quirks[&Quirk::Logic]);
+            assert_eq!(Some(false), platform.quirks.get(Quirk::Shift));
+            assert_eq!(Some(false), platform.quirks.get(Quirk::MemoryIncrementByX));
+            assert_eq!(
+                Some(false),
+                platform.quirks.get(Quirk::MemoryLeaveIUnchanged)
+            );
+            assert_eq!(Some(false), platform.quirks.get(Quirk::Wrap));
+            assert_eq!(Some(false), platform.quirks.get(Quirk::Jump));
+            assert_eq!(Some(true), platform.quirks.get(Quirk::VBlank));
+            assert_eq!(Some(true), platform.quirks.get(Quirk::Logic));
+
+            Ok(())
+        }
+
+        #[test]
+        fn resolutions() -> Result<()> {
+            let input = r##"{
+                "id": "megachip8",
+                "name": "Resolutions Example",
+                "displayResolutions": ["256x192", "64x32", "128x64"],
+                "defaultTickrate": 1000,
+                "quirks": {}
+            }"##;
+
+            let platform: PlatformDetails = serde_json::from_str(input)?;
+
+            assert_eq!(Resolution::new(256, 192), platform.display_resolutions[0]);
+            assert_eq!(
+                r#"["256x192","64x32","128x64"]"#,
+                serde_json::to_string(&platform.display_resolutions)?
+            );
+
+            Ok(())
+        }
+
+        #[test]
+        fn parse_resolution() {
+            assert_eq!(Ok(Resolution::new(64, 32)), "64x32".parse());
+            assert_eq!("128x64", Resolution::new(128, 64).to_string());
+
+            for invalid in [
+                "", "64", "64x", "x32", "64X32", "64x32x2", "-64x32", "64 x 32",
+            ] {
+                assert!(invalid.parse::<Resolution>().is_err(), "{invalid}");
+            }
+
+            let error = serde_json::from_str::<Resolution>(r#""64:32""#).unwrap_err();
+            assert!(error.to_string().contains("is not a resolution"));
+        }
+
+        #[test]
+        fn platform_resolutions() {
+            let db = Database::new();
+
+            for details in &db.platforms {
+                let mut resolutions = details.display_resolutions.clone();
+                resolutions.sort_by_key(Resolution::pixels);
+
+                assert_eq!(
+                    Some(&details.id.lowres()),
+                    resolutions.first(),
+                    "{}",
+                    details.id
+                );
+                assert_eq!(
+                    details.id.hires(),
+                    resolutions.get(1).copied(),
+                    "{}",
+                    details.id
+                );
+            }
+
+            assert_eq!(Resolution::new(64, 32), Platform::OriginalChip8.lowres());
+            assert_eq!(None, Platform::OriginalChip8.hires());
+            assert_eq!(Some(Resolution::new(128, 64)), Platform::MegaChip8.hires());
+            assert!(db.get_platform(Platform::Superchip).is_some());
+        }
+
+        #[test]
+        fn rotated_resolutions() -> Result<()> {
+            let input = r##"{
+                "id": "superchip",
+                "name": "Rotated Platform Example",
+                "displayResolutions": ["64x32", "128x64"],
+                "defaultTickrate": 30,
+                "quirks": {}
+            }"##;
+
+            let platform: PlatformDetails = serde_json::from_str(input)?;
+
+            assert_eq!(
+                vec![Resolution::new(64, 32), Resolution::new(128, 64)],
+                ScreenRotation::LandscapeFlipped.rotated_resolutions(&platform)
+            );
+            assert_eq!(
+                vec![Resolution::new(32, 64), Resolution::new(64, 128)],
+                ScreenRotation::Portrait.rotated_resolutions(&platform)
+            );
 
             Ok(())
         }
@@ -461,16 +2589,19 @@ mod test {
             );
 
             assert_eq!("GPL", &platform.license.unwrap());
-            assert_eq!(vec!["128x64"], platform.display_resolutions);
+            assert_eq!(vec![Resolution::new(128, 64)], platform.display_resolutions);
             assert_eq!(999, platform.default_tickrate);
 
-            assert!(platform.quirks[&Quirk::Shift]);
-            assert!(!platform.quirks[&Quirk::MemoryIncrementByX]);
-            assert!(platform.quirks[&Quirk::MemoryLeaveIUnchanged]);
-            assert!(!platform.quirks[&Quirk::Wrap]);
-            assert!(platform.quirks[&Quirk::Jump]);
-            assert!(!platform.quirks[&Quirk::VBlank]);
-            assert!(platform.quirks[&Quirk::Logic]);
+            assert_eq!(Some(true), platform.quirks.get(Quirk::Shift));
+            assert_eq!(Some(false), platform.quirks.get(Quirk::MemoryIncrementByX));
+            assert_eq!(
+                Some(true),
+                platform.quirks.get(Quirk::MemoryLeaveIUnchanged)
+            );
+            assert_eq!(Some(false), platform.quirks.get(Quirk::Wrap));
+            assert_eq!(Some(true), platform.quirks.get(Quirk::Jump));
+            assert_eq!(Some(false), platform.quirks.get(Quirk::VBlank));
+            assert_eq!(Some(true), platform.quirks.get(Quirk::Logic));
 
             Ok(())
         }
@@ -478,12 +2609,75 @@ mod test {
 
     #[cfg(feature = "extra-data")]
     mod quirks {
-        use crate::quirk::Quirk;
+        use crate::{
+            platform::Platform,
+            quirk::{Quirk, QuirkSet, QuirkSource},
+        };
 
         use super::*;
 
         use std::io::Result;
 
+        #[test]
+        fn effective_quirks() -> Result<()> {
+            let mut db = Database::new();
+
+            db.platforms = serde_json::from_str(
+                r##"[{
+                    "id": "superchip",
+                    "name": "Platform Example",
+                    "displayResolutions": ["128x64"],
+                    "defaultTickrate": 30,
+                    "quirks": { "shift": true, "jump": true }
+                }]"##,
+            )?;
+
+            db.quirks = serde_json::from_str(
+                r##"[{
+                    "id": "vblank",
+                    "name": "Quirk Example",
+                    "default": true,
+                    "ifTrue": "Do some thing",
+                    "ifFalse": "Do some other thing"
+                }]"##,
+            )?;
+
+            let metadata = Metadata {
+                rom: Some(Rom {
+                    quirky_platforms: Some(HashMap::from([(
+                        Platform::Superchip,
+                        QuirkSet::from([(Quirk::Shift, false)]),
+                    )])),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            };
+
+            let quirks = metadata.effective_quirks(&db, Platform::Superchip);
+
+            assert!(!quirks.is_enabled(Quirk::Shift));
+            assert_eq!(QuirkSource::Rom, quirks.get(Quirk::Shift).source);
+
+            assert!(quirks.is_enabled(Quirk::Jump));
+            assert_eq!(QuirkSource::Platform, quirks.get(Quirk::Jump).source);
+
+            assert!(quirks.is_enabled(Quirk::VBlank));
+            assert_eq!(QuirkSource::Default, quirks.get(Quirk::VBlank).source);
+
+            assert!(!quirks.is_enabled(Quirk::Logic));
+            assert_eq!(QuirkSource::Fallback, quirks.get(Quirk::Logic).source);
+
+            assert_eq!(Quirk::ALL.len(), quirks.iter().count());
+            assert_eq!(Quirk::ALL.len(), quirks.to_quirk_set().len());
+
+            assert_eq!(
+                quirks,
+                MetadataRef::from(&metadata).effective_quirks(&db, Platform::Superchip)
+            );
+
+            Ok(())
+        }
+
         #[test]
         fn deserialize_minimal() -> Result<()> {
             let input = r##"{
@@ -0,0 +1,103 @@
+//! Definitions related to merging overlay databases on top of each other.
+
+use crate::{program::Program, Database};
+
+impl Database {
+    /// Merge an overlay database on top of this one, such as private metadata for ROMs that aren't
+    /// in the upstream database yet, or corrections to existing entries.
+    ///
+    /// Precedence rules:
+    ///
+    /// - An overlay program that lists a ROM hash already known to this database is merged into
+    ///   the program that hash belongs to, using [Program::merge]. Overlay fields override per
+    ///   field, and overlay ROMs override per field using [Rom::merge](crate::rom::Rom::merge).
+    /// - If an overlay program lists known hashes belonging to several programs, each known ROM is
+    ///   merged into the program that already has it. The program-level fields and any new ROMs go
+    ///   to the program of the first known hash, in sorted order.
+    /// - An overlay program without any known hashes is appended as a new program.
+    /// - Hashes from earlier overlay programs count as known, so overlay programs that share a new
+    ///   hash are merged into the same program.
+    /// - The overlay's own `hashes` are ignored, and [Database::hashes] is rebuilt from the merged
+    ///   programs, so lookups always return the merged view.
+    /// - With the `extra-data` feature, overlay platforms and quirks replace the entry with the
+    ///   same `id`, or are appended if the `id` is new.
+    pub fn merge(&mut self, overlay: Database) {
+        for mut program in overlay.programs {
+            let mut known: Vec<_> = program
+                .roms
+                .keys()
+                .filter_map(|hash| Some((*hash, *self.hashes.get(hash)?)))
+                .filter(|(_, index)| *index < self.programs.len())
+                .collect();
+
+            known.sort();
+
+            let Some(&(_, target)) = known.first() else {
+                self.record_hashes(&program, self.programs.len());
+                self.programs.push(program);
+                continue;
+            };
+
+            for (hash, index) in known {
+                if index != target {
+                    if let Some(rom) = program.roms.remove(&hash) {
+                        self.programs[index].merge(Program {
+                            roms: [(hash, rom)].into(),
+                            ..Default::default()
+                        });
+                    }
+                }
+            }
+
+            self.record_hashes(&program, target);
+            self.programs[target].merge(program);
+        }
+
+        #[cfg(feature = "extra-data")]
+        {
+            for platform in overlay.platforms {
+                match self.platforms.iter_mut().find(|p| p.id == platform.id) {
+                    Some(existing) => *existing = platform,
+                    None => self.platforms.push(platform),
+                }
+            }
+
+            for quirk in overlay.quirks {
+                match self.quirks.iter_mut().find(|q| q.id == quirk.id) {
+                    Some(existing) => *existing = quirk,
+                    None => self.quirks.push(quirk),
+                }
+            }
+        }
+
+        self.rebuild_hashes();
+    }
+
+    /// Point the hashes of `program` that aren't known yet at the program at `index`.
+    fn record_hashes(&mut self, program: &Program, index: usize) {
+        for hash in program.roms.keys() {
+            self.hashes.entry(*hash).or_insert(index);
+        }
+    }
+
+    /// Rebuild [Database::hashes] from the ROMs listed in each program.
+    ///
+    /// If the same hash is listed under more than one program, the earliest program wins. Use
+    /// [Database::validate] to find such duplicates.
+    pub fn rebuild_hashes(&mut self) {
+        self.hashes.clear();
+
+        for (index, program) in self.programs.iter().enumerate() {
+            for hash in program.roms.keys() {
+                self.hashes.entry(*hash).or_insert(index);
+            }
+        }
+    }
+}
+
+/// Replace `base` with the value of an overlay field, unless the overlay leaves it unset.
+pub(crate) fn replace<T>(base: &mut Option<T>, overlay: Option<T>) {
+    if overlay.is_some() {
+        *base = overlay;
+    }
+}
@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 /// The origin of this program; was it created at some event, for a game jam, or published in a
 /// magazine somewhere?
 #[non_exhaustive]
-#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum OriginType {
     GameJam,
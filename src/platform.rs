@@ -1,11 +1,14 @@
 //! Defintions related to the various CHIP-8 platforms.
 
-use serde::{Deserialize, Serialize};
-use std::fmt::Display;
+use serde::{de::Visitor, Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
 
 /// An ID for this platform, by which to reference it in a [Program].
 #[non_exhaustive]
-#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub enum Platform {
     #[serde(rename = "originalChip8")]
     OriginalChip8,
@@ -55,6 +58,96 @@ impl Display for Platform {
     }
 }
 
+impl Platform {
+    /// Every known platform, in declaration order.
+    pub const ALL: [Platform; 9] = [
+        Platform::OriginalChip8,
+        Platform::HybridVIP,
+        Platform::ModernChip8,
+        Platform::Chip8X,
+        Platform::Chip48,
+        Platform::Superchip1,
+        Platform::Superchip,
+        Platform::MegaChip8,
+        Platform::XOChip,
+    ];
+
+    /// Other platforms that are able to run programs written for this platform, closest first.
+    ///
+    /// An emulator that doesn't implement a ROM's platform can fall back to one of these, as used
+    /// by [Rom::select_platform](crate::rom::Rom::select_platform).
+    pub fn supersets(&self) -> &'static [Platform] {
+        match self {
+            Platform::OriginalChip8 => &[Platform::HybridVIP, Platform::Chip8X],
+            Platform::ModernChip8 => &[Platform::XOChip],
+            Platform::Chip48 => &[
+                Platform::Superchip1,
+                Platform::Superchip,
+                Platform::MegaChip8,
+            ],
+            Platform::Superchip1 => &[Platform::Superchip, Platform::MegaChip8],
+            Platform::Superchip => &[Platform::MegaChip8],
+            Platform::HybridVIP | Platform::Chip8X | Platform::MegaChip8 | Platform::XOChip => &[],
+        }
+    }
+}
+
+/// Why a platform was chosen by [Rom::select_platform](crate::rom::Rom::select_platform).
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum SelectionReason {
+    /// The platform is listed by the ROM itself. A `rank` of `0` is the ROM's preferred platform,
+    /// higher ranks are further down the ROM's list.
+    Listed {
+        /// The position of the platform in the ROM's list of platforms.
+        rank: usize,
+    },
+
+    /// None of the ROM's platforms are supported, but this platform can run programs written for
+    /// one of them. See [Platform::supersets].
+    Superset {
+        /// The platform listed by the ROM that this platform is a superset of.
+        of: Platform,
+    },
+}
+
+impl Display for SelectionReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SelectionReason::Listed { rank: 0 } => write!(f, "it is the ROM's preferred platform"),
+            SelectionReason::Listed { rank } => write!(
+                f,
+                "it is choice #{} of the platforms listed by the ROM",
+                rank + 1
+            ),
+            SelectionReason::Superset { of } => write!(
+                f,
+                "it can run programs written for {of}, which is listed by the ROM"
+            ),
+        }
+    }
+}
+
+/// The platform chosen to run a ROM, along with the reason it was chosen.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct PlatformSelection {
+    /// The platform to run the ROM on.
+    pub platform: Platform,
+
+    /// Why this platform was chosen.
+    pub reason: SelectionReason,
+}
+
+impl Display for PlatformSelection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "running in {} mode because {}",
+            self.platform, self.reason
+        )
+    }
+}
+
 #[cfg(feature = "extra-data")]
 #[non_exhaustive]
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -89,8 +182,11 @@ pub struct PlatformDetails {
     /// https://spdx.org/licenses/).
     pub license: Option<String>,
 
-    /// All the display resolutions that this platform supports in the form "WIDTHxHEIGHT".
-    pub display_resolutions: Vec<String>,
+    /// All the display resolutions that this platform supports, written in the form "WIDTHxHEIGHT".
+    ///
+    /// To size a framebuffer, [Platform::lowres] and [Platform::hires] give the same modes without
+    /// the `extra-data` feature.
+    pub display_resolutions: Vec<Resolution>,
 
     /// The preferred number of cycles per frame to run the interpreter at. It's the default because
     /// ROMs can overwrite this value. CHIP-8 runs at a framerate of 60Hz, so this tickrate times 60
@@ -98,5 +194,111 @@ pub struct PlatformDetails {
     pub default_tickrate: usize,
 
     /// The quirk settings as they are known for this platform.
-    pub quirks: std::collections::HashMap<crate::quirk::Quirk, bool>,
+    pub quirks: crate::quirk::QuirkSet,
+}
+
+/// The size of a display in pixels, written as "WIDTHxHEIGHT" like "64x32".
+///
+/// ```rust
+/// # use chip8_db::platform::Resolution;
+/// #
+/// let resolution: Resolution = "128x64".parse().unwrap();
+///
+/// assert_eq!(Resolution::new(128, 64), resolution);
+/// assert_eq!(8192, resolution.pixels());
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Resolution {
+    /// The width of the display in pixels.
+    pub width: usize,
+
+    /// The height of the display in pixels.
+    pub height: usize,
+}
+
+impl Resolution {
+    /// Create a resolution from its width and height in pixels.
+    pub const fn new(width: usize, height: usize) -> Self {
+        Resolution { width, height }
+    }
+
+    /// The number of pixels on a display of this resolution, such as to allocate a framebuffer.
+    pub const fn pixels(&self) -> usize {
+        self.width * self.height
+    }
+}
+
+impl Display for Resolution {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}x{}", self.width, self.height)
+    }
+}
+
+/// The error returned when a string is not a valid [Resolution].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseResolutionError {
+    input: String,
+}
+
+impl Display for ParseResolutionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?} is not a resolution", self.input)
+    }
+}
+
+impl std::error::Error for ParseResolutionError {}
+
+impl FromStr for Resolution {
+    type Err = ParseResolutionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseResolutionError {
+            input: s.to_owned(),
+        };
+        let number = |digits: &str| {
+            if !digits.is_empty() && digits.bytes().all(|digit| digit.is_ascii_digit()) {
+                digits.parse().map_err(|_| error())
+            } else {
+                Err(error())
+            }
+        };
+
+        let (width, height) = s.split_once('x').ok_or_else(error)?;
+
+        Ok(Resolution::new(number(width)?, number(height)?))
+    }
+}
+
+impl TryFrom<&str> for Resolution {
+    type Error = ParseResolutionError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl Serialize for Resolution {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Resolution {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ResolutionVisitor;
+
+        impl<'de> Visitor<'de> for ResolutionVisitor {
+            type Value = Resolution;
+
+            fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+                write!(f, "a resolution in the form \"WIDTHxHEIGHT\"")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                v.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_str(ResolutionVisitor)
+    }
 }
@@ -4,7 +4,7 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
-use crate::{origin::Origin, rom::Rom};
+use crate::{hash::RomHash, merge::replace, origin::Origin, release::ReleaseDate, rom::Rom};
 
 /// A program written for the CHIP-8 or a derivative platform. Can have multiple versions under the
 /// ROMs section.
@@ -21,8 +21,7 @@ pub struct Program {
     /// year and a month or a year, month and day.
     ///
     /// N.B. When even the specific year is not known, one or more digits at the end may be replaced
-    /// with `x`, as in `199x` or `19xx`. If sorting is required, sorting as strings should do the
-    /// job.
+    /// with `x`, as in `199x` or `19xx`. Use [Program::release_date] to sort or compare releases.
     pub release: Option<String>,
 
     /// The origin of this program; was it created at some event, for a game jam, or published in a
@@ -49,5 +48,42 @@ pub struct Program {
     pub urls: Option<Vec<String>>,
 
     /// A map of SHA1 hashes to ROM files that relate to this program.
-    pub roms: HashMap<String, Rom>,
+    pub roms: HashMap<RomHash, Rom>,
+}
+
+impl Program {
+    /// The parsed [release](Program::release) date, or `None` if it is unknown or not a valid
+    /// [ReleaseDate].
+    pub fn release_date(&self) -> Option<ReleaseDate> {
+        self.release.as_deref()?.parse().ok()
+    }
+
+    /// Merge the fields of `overlay` into this program, with `overlay` taking precedence.
+    ///
+    /// A non-empty `title` and every optional field that is set in `overlay` replace the matching
+    /// field in `self`. ROMs with a hash that is already known are merged with [Rom::merge], and
+    /// new ROMs are added.
+    pub fn merge(&mut self, overlay: Program) {
+        if !overlay.title.is_empty() {
+            self.title = overlay.title;
+        }
+
+        replace(&mut self.description, overlay.description);
+        replace(&mut self.release, overlay.release);
+        replace(&mut self.origin, overlay.origin);
+        replace(&mut self.copyright, overlay.copyright);
+        replace(&mut self.license, overlay.license);
+        replace(&mut self.authors, overlay.authors);
+        replace(&mut self.images, overlay.images);
+        replace(&mut self.urls, overlay.urls);
+
+        for (hash, rom) in overlay.roms {
+            match self.roms.get_mut(&hash) {
+                Some(existing) => existing.merge(rom),
+                None => {
+                    self.roms.insert(hash, rom);
+                }
+            }
+        }
+    }
 }
@@ -1,9 +1,17 @@
 //! Defintions related to CHIP-8 quirks.
 
-use serde::{Deserialize, Serialize};
+use serde::{
+    de::{MapAccess, Visitor},
+    ser::SerializeMap,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use std::fmt::Formatter;
+
+#[cfg(feature = "extra-data")]
+use crate::{platform::Platform, Database, Metadata, MetadataRef};
 
 /// An ID for each quirk, by which to reference it in a [Program] or [Platform].
-#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub enum Quirk {
     Shift,
@@ -16,6 +24,214 @@ pub enum Quirk {
     Logic,
 }
 
+impl Quirk {
+    /// Every known quirk, in declaration order.
+    pub const ALL: [Quirk; 7] = [
+        Quirk::Shift,
+        Quirk::MemoryIncrementByX,
+        Quirk::MemoryLeaveIUnchanged,
+        Quirk::Wrap,
+        Quirk::Jump,
+        Quirk::VBlank,
+        Quirk::Logic,
+    ];
+}
+
+/// A compact set of quirk settings, where each [Quirk] is either enabled, disabled or unspecified.
+///
+/// This is stored as a pair of bitmasks, so it's cheap to copy and to query in an emulator's hot
+/// path. It (de)serializes as the same JSON object as the database, such as
+/// `{ "shift": true, "vblank": false }`, with unspecified quirks left out.
+#[derive(Clone, Copy, Default, Eq, Hash, PartialEq)]
+pub struct QuirkSet {
+    /// A bit for each quirk that has a value.
+    specified: u8,
+
+    /// A bit for each quirk that is enabled. Always a subset of `specified`.
+    enabled: u8,
+}
+
+impl QuirkSet {
+    /// Create a set where every quirk is unspecified.
+    pub const fn new() -> Self {
+        QuirkSet {
+            specified: 0,
+            enabled: 0,
+        }
+    }
+
+    const fn bit(quirk: Quirk) -> u8 {
+        1 << quirk as u8
+    }
+
+    /// Get the value of a quirk, or `None` if it's unspecified.
+    pub const fn get(&self, quirk: Quirk) -> Option<bool> {
+        let bit = Self::bit(quirk);
+
+        if self.specified & bit == 0 {
+            None
+        } else {
+            Some(self.enabled & bit != 0)
+        }
+    }
+
+    /// Whether a quirk is enabled, treating unspecified quirks as disabled.
+    pub const fn is_enabled(&self, quirk: Quirk) -> bool {
+        self.enabled & Self::bit(quirk) != 0
+    }
+
+    /// Whether a quirk has a value in this set.
+    pub const fn contains(&self, quirk: Quirk) -> bool {
+        self.specified & Self::bit(quirk) != 0
+    }
+
+    /// Return a copy of this set with a quirk set to a value.
+    pub const fn with(self, quirk: Quirk, enabled: bool) -> Self {
+        let bit = Self::bit(quirk);
+
+        QuirkSet {
+            specified: self.specified | bit,
+            enabled: if enabled {
+                self.enabled | bit
+            } else {
+                self.enabled & !bit
+            },
+        }
+    }
+
+    /// Set a quirk to a value.
+    pub fn set(&mut self, quirk: Quirk, enabled: bool) {
+        *self = self.with(quirk, enabled);
+    }
+
+    /// Make a quirk unspecified again.
+    pub fn remove(&mut self, quirk: Quirk) {
+        let bit = Self::bit(quirk);
+
+        self.specified &= !bit;
+        self.enabled &= !bit;
+    }
+
+    /// The number of quirks with a value in this set.
+    pub const fn len(&self) -> usize {
+        self.specified.count_ones() as usize
+    }
+
+    /// Whether every quirk is unspecified.
+    pub const fn is_empty(&self) -> bool {
+        self.specified == 0
+    }
+
+    /// Iterate over every specified quirk and its value, in the order of [Quirk::ALL].
+    pub fn iter(&self) -> Iter {
+        Iter {
+            set: *self,
+            index: 0,
+        }
+    }
+}
+
+/// An iterator over the specified quirks of a [QuirkSet] and their values, created by
+/// [QuirkSet::iter].
+#[derive(Clone, Debug)]
+pub struct Iter {
+    set: QuirkSet,
+
+    /// The position in [Quirk::ALL] of the next quirk to check.
+    index: usize,
+}
+
+impl Iterator for Iter {
+    type Item = (Quirk, bool);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(&quirk) = Quirk::ALL.get(self.index) {
+            self.index += 1;
+
+            if let Some(enabled) = self.set.get(quirk) {
+                return Some((quirk, enabled));
+            }
+        }
+
+        None
+    }
+}
+
+impl std::fmt::Debug for QuirkSet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl FromIterator<(Quirk, bool)> for QuirkSet {
+    fn from_iter<T: IntoIterator<Item = (Quirk, bool)>>(iter: T) -> Self {
+        let mut set = QuirkSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<const N: usize> From<[(Quirk, bool); N]> for QuirkSet {
+    fn from(quirks: [(Quirk, bool); N]) -> Self {
+        quirks.into_iter().collect()
+    }
+}
+
+impl Extend<(Quirk, bool)> for QuirkSet {
+    fn extend<T: IntoIterator<Item = (Quirk, bool)>>(&mut self, iter: T) {
+        for (quirk, enabled) in iter {
+            self.set(quirk, enabled);
+        }
+    }
+}
+
+impl IntoIterator for QuirkSet {
+    type Item = (Quirk, bool);
+    type IntoIter = Iter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl Serialize for QuirkSet {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.len()))?;
+
+        for (quirk, enabled) in self.iter() {
+            map.serialize_entry(&quirk, &enabled)?;
+        }
+
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for QuirkSet {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct QuirkSetVisitor;
+
+        impl<'de> Visitor<'de> for QuirkSetVisitor {
+            type Value = QuirkSet;
+
+            fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+                write!(f, "a map of quirks to booleans")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut set = QuirkSet::new();
+
+                while let Some((quirk, enabled)) = map.next_entry()? {
+                    set.set(quirk, enabled);
+                }
+
+                Ok(set)
+            }
+        }
+
+        deserializer.deserialize_map(QuirkSetVisitor)
+    }
+}
+
 /// A detailed breakdown of the meaning of a [Quirk].
 #[cfg(feature = "extra-data")]
 #[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
@@ -39,3 +255,118 @@ pub struct QuirkDetails {
     /// A description of CHIP-8 behavior when this quirk is not enabled.
     pub if_false: String,
 }
+
+/// Where the value of a resolved quirk came from.
+#[cfg(feature = "extra-data")]
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum QuirkSource {
+    /// The ROM overrides this quirk for the chosen platform in its `quirky_platforms`.
+    Rom,
+
+    /// The quirk is set by the chosen platform in `platforms.json`.
+    Platform,
+
+    /// The quirk uses its global default from `quirks.json`.
+    Default,
+
+    /// No source had a value for this quirk, so it is assumed to be disabled.
+    Fallback,
+}
+
+/// The value of a single quirk, along with where that value came from.
+#[cfg(feature = "extra-data")]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct ResolvedQuirk {
+    /// Whether the quirk should be enabled.
+    pub enabled: bool,
+
+    /// Where the value of `enabled` came from.
+    pub source: QuirkSource,
+}
+
+/// A complete set of quirks for running a ROM on a specific platform, with every [Quirk] resolved.
+#[cfg(feature = "extra-data")]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct EffectiveQuirks {
+    quirks: [ResolvedQuirk; Quirk::ALL.len()],
+}
+
+#[cfg(feature = "extra-data")]
+impl EffectiveQuirks {
+    /// Get the resolved value of a quirk, along with where it came from.
+    pub fn get(&self, quirk: Quirk) -> ResolvedQuirk {
+        self.quirks[quirk as usize]
+    }
+
+    /// Whether a quirk should be enabled.
+    pub fn is_enabled(&self, quirk: Quirk) -> bool {
+        self.get(quirk).enabled
+    }
+
+    /// Iterate over every quirk and its resolved value, in the order of [Quirk::ALL].
+    pub fn iter(&self) -> impl Iterator<Item = (Quirk, ResolvedQuirk)> + '_ {
+        Quirk::ALL.into_iter().zip(self.quirks)
+    }
+
+    /// Convert to a [QuirkSet] with every quirk specified, for cheap lookups while emulating.
+    pub fn to_quirk_set(&self) -> QuirkSet {
+        self.iter()
+            .map(|(quirk, resolved)| (quirk, resolved.enabled))
+            .collect()
+    }
+}
+
+#[cfg(feature = "extra-data")]
+impl MetadataRef<'_> {
+    /// Resolve the full set of quirks for running this ROM on `platform`.
+    ///
+    /// Each quirk is taken from the first of these sources that has a value for it:
+    ///
+    /// 1. The ROM's `quirky_platforms` entry for `platform`.
+    /// 2. The quirks of `platform` in `platforms.json`.
+    /// 3. The global default of the quirk in `quirks.json`.
+    ///
+    /// If none of them has a value, the quirk is disabled with [QuirkSource::Fallback].
+    pub fn effective_quirks(&self, db: &Database, platform: Platform) -> EffectiveQuirks {
+        let rom_quirks = self
+            .rom
+            .and_then(|rom| rom.quirky_platforms.as_ref())
+            .and_then(|platforms| platforms.get(&platform));
+
+        let platform_quirks = db.get_platform(platform).map(|details| &details.quirks);
+
+        let resolve = |quirk: Quirk| {
+            let (enabled, source) = rom_quirks
+                .and_then(|quirks| quirks.get(quirk))
+                .map(|enabled| (enabled, QuirkSource::Rom))
+                .or_else(|| {
+                    platform_quirks
+                        .and_then(|quirks| quirks.get(quirk))
+                        .map(|enabled| (enabled, QuirkSource::Platform))
+                })
+                .or_else(|| {
+                    db.quirks
+                        .iter()
+                        .find(|details| details.id == quirk)
+                        .map(|details| (details.default, QuirkSource::Default))
+                })
+                .unwrap_or((false, QuirkSource::Fallback));
+
+            ResolvedQuirk { enabled, source }
+        };
+
+        EffectiveQuirks {
+            quirks: Quirk::ALL.map(resolve),
+        }
+    }
+}
+
+#[cfg(feature = "extra-data")]
+impl Metadata {
+    /// Resolve the full set of quirks for running this ROM on `platform`. See
+    /// [MetadataRef::effective_quirks].
+    pub fn effective_quirks(&self, db: &Database, platform: Platform) -> EffectiveQuirks {
+        MetadataRef::from(self).effective_quirks(db, platform)
+    }
+}
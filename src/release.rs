@@ -0,0 +1,247 @@
+//! Definitions related to the release dates of programs and ROMs.
+
+use std::{
+    cmp::Ordering,
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+use serde::{de::Visitor, Deserialize, Deserializer, Serialize, Serializer};
+
+/// A release date in the ISO 8601 date format used by the database, with the precision it was
+/// known to.
+///
+/// When even the year is not known, the last digits may be replaced with `x`, as in `199x` or
+/// `19xx`. Dates are ordered by the first day they could refer to, and less precise dates come
+/// before more precise ones that start on the same day.
+///
+/// ```rust
+/// # use chip8_db::release::ReleaseDate;
+/// #
+/// let dates: Vec<ReleaseDate> = ["1990-05", "199x", "1978", "1990"]
+///     .into_iter()
+///     .map(|date| date.parse().unwrap())
+///     .collect();
+///
+/// let mut sorted = dates.clone();
+/// sorted.sort();
+///
+/// let sorted: Vec<String> = sorted.iter().map(ReleaseDate::to_string).collect();
+///
+/// assert_eq!(vec!["1978", "199x", "1990", "1990-05"], sorted);
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ReleaseDate {
+    /// Only the century is known, written as `19xx`. Holds the first year of the century.
+    Century(u16),
+
+    /// Only the decade is known, written as `199x`. Holds the first year of the decade.
+    Decade(u16),
+
+    /// Only the year is known, written as `1978`.
+    Year(u16),
+
+    /// The year and month are known, written as `1990-05`.
+    Month {
+        /// The year of release.
+        year: u16,
+
+        /// The month of release, from 1 to 12.
+        month: u8,
+    },
+
+    /// The exact day is known, written as `2023-06-24`.
+    Day {
+        /// The year of release.
+        year: u16,
+
+        /// The month of release, from 1 to 12.
+        month: u8,
+
+        /// The day of the month of release, starting from 1.
+        day: u8,
+    },
+}
+
+impl ReleaseDate {
+    /// The first year this date could refer to.
+    pub const fn year(&self) -> u16 {
+        match *self {
+            ReleaseDate::Century(year)
+            | ReleaseDate::Decade(year)
+            | ReleaseDate::Year(year)
+            | ReleaseDate::Month { year, .. }
+            | ReleaseDate::Day { year, .. } => year,
+        }
+    }
+
+    /// The month of release, if known.
+    pub const fn month(&self) -> Option<u8> {
+        match *self {
+            ReleaseDate::Month { month, .. } | ReleaseDate::Day { month, .. } => Some(month),
+            _ => None,
+        }
+    }
+
+    /// The day of the month of release, if known.
+    pub const fn day(&self) -> Option<u8> {
+        match *self {
+            ReleaseDate::Day { day, .. } => Some(day),
+            _ => None,
+        }
+    }
+
+    /// How precise this date is, from `0` for a century to `4` for an exact day.
+    const fn precision(&self) -> u8 {
+        match self {
+            ReleaseDate::Century(_) => 0,
+            ReleaseDate::Decade(_) => 1,
+            ReleaseDate::Year(_) => 2,
+            ReleaseDate::Month { .. } => 3,
+            ReleaseDate::Day { .. } => 4,
+        }
+    }
+
+    fn sort_key(&self) -> (u16, u8, u8, u8) {
+        (
+            self.year(),
+            self.month().unwrap_or(1),
+            self.day().unwrap_or(1),
+            self.precision(),
+        )
+    }
+}
+
+impl Ord for ReleaseDate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.sort_key().cmp(&other.sort_key())
+    }
+}
+
+impl PartialOrd for ReleaseDate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for ReleaseDate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            ReleaseDate::Century(year) => write!(f, "{:02}xx", year / 100),
+            ReleaseDate::Decade(year) => write!(f, "{:03}x", year / 10),
+            ReleaseDate::Year(year) => write!(f, "{year:04}"),
+            ReleaseDate::Month { year, month } => write!(f, "{year:04}-{month:02}"),
+            ReleaseDate::Day { year, month, day } => write!(f, "{year:04}-{month:02}-{day:02}"),
+        }
+    }
+}
+
+/// The error returned when a string is not a valid [ReleaseDate].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseReleaseDateError {
+    input: String,
+}
+
+impl Display for ParseReleaseDateError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?} is not a release date", self.input)
+    }
+}
+
+impl std::error::Error for ParseReleaseDateError {}
+
+impl FromStr for ReleaseDate {
+    type Err = ParseReleaseDateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseReleaseDateError {
+            input: s.to_owned(),
+        };
+        let number = |digits: &str, len| {
+            if digits.len() == len && digits.bytes().all(|digit| digit.is_ascii_digit()) {
+                digits.parse::<u16>().map_err(|_| error())
+            } else {
+                Err(error())
+            }
+        };
+
+        let mut parts = s.split('-');
+        let year = parts.next().unwrap_or_default();
+
+        let date = if let Some(century) = year.strip_suffix("xx") {
+            ReleaseDate::Century(number(century, 2)? * 100)
+        } else if let Some(decade) = year.strip_suffix('x') {
+            ReleaseDate::Decade(number(decade, 3)? * 10)
+        } else {
+            let year = number(year, 4)?;
+
+            match (parts.next(), parts.next()) {
+                (None, _) => ReleaseDate::Year(year),
+                (Some(month), None) => ReleaseDate::Month {
+                    year,
+                    month: number(month, 2)? as u8,
+                },
+                (Some(month), Some(day)) => ReleaseDate::Day {
+                    year,
+                    month: number(month, 2)? as u8,
+                    day: number(day, 2)? as u8,
+                },
+            }
+        };
+
+        let valid_month = date.month().map_or(true, |month| (1..=12).contains(&month));
+        let valid_day = match (date.month(), date.day()) {
+            (Some(month), Some(day)) => day >= 1 && day <= days_in_month(date.year(), month),
+            _ => true,
+        };
+
+        if parts.next().is_some() || !valid_month || !valid_day {
+            return Err(error());
+        }
+
+        Ok(date)
+    }
+}
+
+impl TryFrom<&str> for ReleaseDate {
+    type Error = ParseReleaseDateError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl Serialize for ReleaseDate {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ReleaseDate {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ReleaseDateVisitor;
+
+        impl<'de> Visitor<'de> for ReleaseDateVisitor {
+            type Value = ReleaseDate;
+
+            fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+                write!(f, "a release date in ISO 8601 date format")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                v.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_str(ReleaseDateVisitor)
+    }
+}
+
+const fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
@@ -8,8 +8,10 @@ use crate::{
     color::Colors,
     font::FontStyle,
     input::{Keymap, TouchInputMode},
-    platform::Platform,
-    quirk::Quirk,
+    merge::replace,
+    platform::{Platform, PlatformSelection, SelectionReason},
+    quirk::QuirkSet,
+    release::ReleaseDate,
     rotation::ScreenRotation,
 };
 
@@ -33,8 +35,7 @@ pub struct Rom {
     /// year, a year and a month or a year, month and day.
     ///
     /// N.B. When even the specific year is not known, one or more digits at the end may be replaced
-    /// with `x`, as in `199x` or `19xx`. If sorting is required, sorting as strings should do the
-    /// job.
+    /// with `x`, as in `199x` or `19xx`. Use [Rom::release_date] to sort or compare releases.
     pub release: Option<String>,
 
     /// An ordered list of the platforms that this ROM can successfully run on with that platform's
@@ -48,7 +49,7 @@ pub struct Rom {
     /// cases, this map of quirky platforms can be used to override the quirks of a base platform.
     /// If an interpreter supports quirky platforms, a quirky platform should be preferred over the
     /// regular [platforms] list. Otherwise, `quirky_platforms` should just be ignored.
-    pub quirky_platforms: Option<HashMap<Platform, HashMap<Quirk, bool>>>,
+    pub quirky_platforms: Option<HashMap<Platform, QuirkSet>>,
 
     /// The list of authors who worked on developing this ROM.
     pub authors: Option<Vec<String>>,
@@ -84,3 +85,83 @@ pub struct Rom {
     /// An object with hexadecimal color properties (#RRGGBB).
     pub colors: Option<Colors>,
 }
+
+impl Rom {
+    /// The parsed [release](Rom::release) date, or `None` if it is unknown or not a valid
+    /// [ReleaseDate].
+    pub fn release_date(&self) -> Option<ReleaseDate> {
+        self.release.as_deref()?.parse().ok()
+    }
+
+    /// Pick the best platform to run this ROM on, out of the platforms an emulator supports.
+    ///
+    /// Platforms are chosen in this order of preference:
+    ///
+    /// 1. The first of the ROM's [platforms](Rom::platforms) that is supported, since that list is
+    ///    sorted from best to worst gameplay.
+    /// 2. The closest supported superset of one of the ROM's platforms, following the order of
+    ///    [Platform::supersets]. For example, SUPER-CHIP 1.0 is preferred over SUPER-CHIP for a
+    ///    CHIP-48 ROM.
+    ///
+    /// Returns `None` if no supported platform can run this ROM.
+    pub fn select_platform(&self, supported: &[Platform]) -> Option<PlatformSelection> {
+        let listed = self
+            .platforms
+            .iter()
+            .enumerate()
+            .find_map(|(rank, platform)| {
+                supported.contains(platform).then_some(PlatformSelection {
+                    platform: *platform,
+                    reason: SelectionReason::Listed { rank },
+                })
+            });
+
+        listed.or_else(|| {
+            self.platforms.iter().find_map(|of| {
+                of.supersets()
+                    .iter()
+                    .find(|superset| supported.contains(superset))
+                    .map(|platform| PlatformSelection {
+                        platform: *platform,
+                        reason: SelectionReason::Superset { of: *of },
+                    })
+            })
+        })
+    }
+
+    /// Merge the fields of `overlay` into this ROM, with `overlay` taking precedence.
+    ///
+    /// Every field that is set in `overlay` replaces the matching field in `self`, while fields
+    /// that are unset (`None`, or an empty list of `platforms`) keep their current value. The
+    /// `quirky_platforms` are merged per platform and per quirk, so an overlay can change a single
+    /// quirk without repeating the rest.
+    pub fn merge(&mut self, overlay: Rom) {
+        replace(&mut self.file_name, overlay.file_name);
+        replace(&mut self.embedded_title, overlay.embedded_title);
+        replace(&mut self.description, overlay.description);
+        replace(&mut self.release, overlay.release);
+
+        if !overlay.platforms.is_empty() {
+            self.platforms = overlay.platforms;
+        }
+
+        if let Some(overlay_platforms) = overlay.quirky_platforms {
+            let quirky_platforms = self.quirky_platforms.get_or_insert_with(HashMap::new);
+
+            for (platform, quirks) in overlay_platforms {
+                quirky_platforms.entry(platform).or_default().extend(quirks);
+            }
+        }
+
+        replace(&mut self.authors, overlay.authors);
+        replace(&mut self.images, overlay.images);
+        replace(&mut self.urls, overlay.urls);
+        replace(&mut self.tickrate, overlay.tickrate);
+        replace(&mut self.start_address, overlay.start_address);
+        replace(&mut self.screen_rotation, overlay.screen_rotation);
+        replace(&mut self.keys, overlay.keys);
+        replace(&mut self.touch_input_mode, overlay.touch_input_mode);
+        replace(&mut self.font_style, overlay.font_style);
+        replace(&mut self.colors, overlay.colors);
+    }
+}
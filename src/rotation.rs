@@ -1,9 +1,9 @@
-//! Defintions related to CHIP-8 screen rotations.
+//! Definitions related to CHIP-8 screen rotations.
 
 use serde_repr::{Deserialize_repr, Serialize_repr};
 
 /// The screen orientation, in degrees rotated clockwise from the normal position.
-#[derive(Clone, Debug, Default, Deserialize_repr, Eq, Hash, PartialEq, Serialize_repr)]
+#[derive(Clone, Copy, Debug, Default, Deserialize_repr, Eq, Hash, PartialEq, Serialize_repr)]
 #[repr(usize)]
 pub enum ScreenRotation {
     /// A landscape view - the default orientation.
@@ -19,3 +19,114 @@ pub enum ScreenRotation {
     /// A portrait view - rotated 270 degrees clockwise from the default.
     PortraitFlipped = 270,
 }
+
+impl ScreenRotation {
+    /// Whether the width and height of the display are swapped by this rotation.
+    pub const fn is_portrait(self) -> bool {
+        matches!(
+            self,
+            ScreenRotation::Portrait | ScreenRotation::PortraitFlipped
+        )
+    }
+
+    /// The size of a display of `width` by `height` pixels after rotating it.
+    pub const fn rotated_size(self, width: usize, height: usize) -> (usize, usize) {
+        if self.is_portrait() {
+            (height, width)
+        } else {
+            (width, height)
+        }
+    }
+
+    /// The position on the rotated display of pixel `(x, y)` of a CHIP-8 display of `width` by
+    /// `height` pixels.
+    ///
+    /// Returns `None` if `(x, y)` lies outside of the CHIP-8 display.
+    pub const fn to_rotated(
+        self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    ) -> Option<(usize, usize)> {
+        if x >= width || y >= height {
+            return None;
+        }
+
+        Some(self.rotate_point(x, y, width, height))
+    }
+
+    /// The pixel of a CHIP-8 display of `width` by `height` pixels shown at position `(x, y)` of
+    /// the rotated display, such as the pixel under a mouse pointer or touch.
+    ///
+    /// Returns `None` if `(x, y)` lies outside of the rotated display, such as a pointer in the
+    /// margins around it.
+    pub const fn to_chip8(
+        self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    ) -> Option<(usize, usize)> {
+        let (rotated_width, rotated_height) = self.rotated_size(width, height);
+
+        if x >= rotated_width || y >= rotated_height {
+            return None;
+        }
+
+        Some(match self {
+            ScreenRotation::Landscape => (x, y),
+            ScreenRotation::Portrait => (y, height - 1 - x),
+            ScreenRotation::LandscapeFlipped => (width - 1 - x, height - 1 - y),
+            ScreenRotation::PortraitFlipped => (width - 1 - y, x),
+        })
+    }
+
+    /// [ScreenRotation::to_rotated] for a pixel that is known to lie on the display.
+    const fn rotate_point(self, x: usize, y: usize, width: usize, height: usize) -> (usize, usize) {
+        match self {
+            ScreenRotation::Landscape => (x, y),
+            ScreenRotation::Portrait => (height - 1 - y, x),
+            ScreenRotation::LandscapeFlipped => (width - 1 - x, height - 1 - y),
+            ScreenRotation::PortraitFlipped => (y, width - 1 - x),
+        }
+    }
+
+    /// Rotate a framebuffer of `width` by `height` pixels, stored row by row, into `output`, which
+    /// is stored row by row with the [rotated size](ScreenRotation::rotated_size).
+    ///
+    /// Pixels can be of any type, such as a `bool` for monochrome displays or a `u8` holding the
+    /// bits of every plane for multi-plane displays.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `framebuffer` or `output` don't hold exactly `width * height` pixels.
+    pub fn rotate<T: Copy>(self, framebuffer: &[T], width: usize, height: usize, output: &mut [T]) {
+        assert_eq!(width * height, framebuffer.len(), "framebuffer size");
+        assert_eq!(width * height, output.len(), "output size");
+
+        let (rotated_width, _) = self.rotated_size(width, height);
+
+        for (i, &pixel) in framebuffer.iter().enumerate() {
+            let (x, y) = self.rotate_point(i % width, i / width, width, height);
+            output[y * rotated_width + x] = pixel;
+        }
+    }
+
+    /// Every display resolution of a platform after rotating it.
+    #[cfg(feature = "extra-data")]
+    pub fn rotated_resolutions(
+        self,
+        platform: &crate::platform::PlatformDetails,
+    ) -> Vec<crate::platform::Resolution> {
+        platform
+            .display_resolutions
+            .iter()
+            .map(|resolution| {
+                let (width, height) = self.rotated_size(resolution.width, resolution.height);
+
+                crate::platform::Resolution::new(width, height)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,198 @@
+//! Definitions related to searching programs by their titles, descriptions and authors.
+//!
+//! ```rust
+//! # use chip8_db::Database;
+//! #
+//! let db = Database::new();
+//! let index = db.search_index();
+//!
+//! // Build the index once, then search as often as needed
+//! for hit in index.search("space inv") {
+//!     println!("{} ({})", hit.program.title, hit.score);
+//! }
+//! ```
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::Bound::{Included, Unbounded},
+};
+
+use crate::{program::Program, Database};
+
+/// A field of a program that can match a search query.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Field {
+    /// [Program.title](Program::title).
+    Title,
+
+    /// The [embedded title](crate::rom::Rom::embedded_title) of any of the program's ROMs.
+    EmbeddedTitle,
+
+    /// The authors of the program or any of its ROMs.
+    Author,
+
+    /// The description of the program or any of its ROMs.
+    Description,
+}
+
+impl Field {
+    /// How much a match in this field counts towards the score of a hit. Each field outweighs every
+    /// field after it, even when the match in the later field is a whole word.
+    pub const fn weight(self) -> u32 {
+        match self {
+            Field::Title => 16,
+            Field::EmbeddedTitle => 8,
+            Field::Author => 4,
+            Field::Description => 2,
+        }
+    }
+}
+
+/// An inverted index of every word in the programs of a [Database], fast enough to search on every
+/// keystroke.
+#[derive(Clone, Debug)]
+pub struct SearchIndex<'db> {
+    programs: &'db [Program],
+    words: BTreeMap<String, Vec<(usize, Field)>>,
+}
+
+/// A program that matched a search query.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SearchHit<'db> {
+    /// The index of the program in [Database.programs](Database::programs).
+    pub index: usize,
+
+    /// The program that matched.
+    pub program: &'db Program,
+
+    /// How well the program matched, higher is better.
+    ///
+    /// Every word of the query adds the [weight](Field::weight) of the best field it appeared in,
+    /// plus one if it matched a whole word rather than the start of one.
+    pub score: u32,
+}
+
+impl Database {
+    /// Build an index to search the programs in this database.
+    pub fn search_index(&self) -> SearchIndex<'_> {
+        SearchIndex::new(&self.programs)
+    }
+}
+
+impl<'db> SearchIndex<'db> {
+    /// Index every program in `programs`.
+    pub fn new(programs: &'db [Program]) -> Self {
+        let mut words: BTreeMap<String, Vec<(usize, Field)>> = BTreeMap::new();
+
+        for (index, program) in programs.iter().enumerate() {
+            let mut add = |field, text: &str| {
+                for word in tokenize(text) {
+                    words.entry(word).or_default().push((index, field));
+                }
+            };
+
+            add(Field::Title, &program.title);
+
+            for rom in program.roms.values() {
+                if let Some(title) = &rom.embedded_title {
+                    add(Field::EmbeddedTitle, title);
+                }
+            }
+
+            let authors = program.roms.values().flat_map(|rom| &rom.authors);
+
+            for author in program.authors.iter().chain(authors).flatten() {
+                add(Field::Author, author);
+            }
+
+            let descriptions = program.roms.values().map(|rom| &rom.description);
+
+            for description in std::iter::once(&program.description)
+                .chain(descriptions)
+                .flatten()
+            {
+                add(Field::Description, description);
+            }
+        }
+
+        for postings in words.values_mut() {
+            postings.sort_unstable();
+            postings.dedup();
+        }
+
+        SearchIndex { programs, words }
+    }
+
+    /// Find every program that matches all words in `query`, best first.
+    ///
+    /// Matching is case-insensitive, and each word of the query also matches longer words that
+    /// start with it, so partially typed queries find results as well. Programs with the same
+    /// score are sorted by title.
+    pub fn search(&self, query: &str) -> Vec<SearchHit<'db>> {
+        let query = tokenize(query);
+
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let mut scores: HashMap<usize, u32> = HashMap::new();
+
+        for (i, word) in query.iter().enumerate() {
+            let mut best: HashMap<usize, u32> = HashMap::new();
+
+            for (indexed, postings) in self
+                .words
+                .range::<str, _>((Included(word.as_str()), Unbounded))
+            {
+                if !indexed.starts_with(word.as_str()) {
+                    break;
+                }
+
+                let bonus = u32::from(indexed == word);
+
+                for &(index, field) in postings {
+                    let score = best.entry(index).or_default();
+                    *score = (*score).max(field.weight() + bonus);
+                }
+            }
+
+            if i == 0 {
+                scores = best;
+            } else {
+                scores.retain(|index, score| match best.get(index) {
+                    Some(best) => {
+                        *score += best;
+                        true
+                    }
+                    None => false,
+                });
+            }
+        }
+
+        let mut hits: Vec<_> = scores
+            .into_iter()
+            .map(|(index, score)| SearchHit {
+                index,
+                program: &self.programs[index],
+                score,
+            })
+            .collect();
+
+        hits.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| a.program.title.cmp(&b.program.title))
+                .then(a.index.cmp(&b.index))
+        });
+
+        hits
+    }
+}
+
+/// Split text into lowercase words, ignoring punctuation.
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
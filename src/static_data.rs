@@ -0,0 +1,211 @@
+//! A copy of the ROM database generated at compile time, enabled with the `static-data` feature.
+//!
+//! Unlike [Database](crate::Database), nothing here is parsed or allocated at runtime. Every
+//! program and ROM is a `static` borrowed from the binary, and hashes are looked up in a perfect
+//! hash map.
+//!
+//! ```rust
+//! # use chip8_db::static_data;
+//! #
+//! let hash = "0df2789f661358d8f7370e6cf93490c5bcd44b01".parse().unwrap();
+//!
+//! if let Some((program, rom)) = static_data::lookup(hash) {
+//!     println!("Title: {} ({:?})", program.title, rom.file_name);
+//! }
+//! ```
+
+use crate::{
+    color::Colors,
+    font::FontStyle,
+    hash::RomHash,
+    input::{Keymap, TouchInputMode},
+    origin::{Origin, OriginType},
+    platform::Platform,
+    program::Program,
+    quirk::{Quirk, QuirkSet},
+    rom::Rom,
+    rotation::ScreenRotation,
+};
+
+include!(concat!(env!("OUT_DIR"), "/static_data.rs"));
+
+/// A static version of [Program], generated at compile time.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct StaticProgram {
+    /// The title of the program, preferably in the way the original author intended it.
+    pub title: &'static str,
+
+    /// A description of the program, preferably in the way the original author described or
+    /// published it.
+    pub description: Option<&'static str>,
+
+    /// The date at which the program was first released in ISO 8601 date format.
+    pub release: Option<&'static str>,
+
+    /// The origin of this program; was it created at some event, for a game jam, or published in a
+    /// magazine somewhere?
+    pub origin: Option<StaticOrigin>,
+
+    /// The copyright situation of this program.
+    pub copyright: Option<&'static str>,
+
+    /// The license(s) applicable to this program, as an SPDX license expression.
+    pub license: Option<&'static str>,
+
+    /// The list of authors who worked on developing this program.
+    pub authors: Option<&'static [&'static str]>,
+
+    /// A list of file names of images that display this program.
+    pub images: Option<&'static [&'static str]>,
+
+    /// A list of URLs that are relevant for this program.
+    pub urls: Option<&'static [&'static str]>,
+
+    /// A list of SHA1 hashes and the ROM files that relate to this program.
+    pub roms: &'static [(RomHash, StaticRom)],
+}
+
+/// A static version of [Origin], generated at compile time.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct StaticOrigin {
+    /// The kind of origin of this program.
+    pub origin_type: Option<OriginType>,
+
+    /// A freeform reference to the origin of this program.
+    pub reference: Option<&'static str>,
+}
+
+/// A static version of [Rom], generated at compile time.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct StaticRom {
+    /// The file name of this ROM as it was observed when added to the database.
+    pub file_name: Option<&'static str>,
+
+    /// The title that was extracted from the ROM file.
+    pub embedded_title: Option<&'static str>,
+
+    /// Anything relevant about this specific ROM that isn't already in the program description.
+    pub description: Option<&'static str>,
+
+    /// The date at which this particular ROM was first released in ISO 8601 date format.
+    pub release: Option<&'static str>,
+
+    /// An ordered list of the platforms that this ROM can successfully run on, best first.
+    pub platforms: &'static [Platform],
+
+    /// Overrides of the quirks of a base platform for this ROM.
+    pub quirky_platforms: Option<&'static [(Platform, QuirkSet)]>,
+
+    /// The list of authors who worked on developing this ROM.
+    pub authors: Option<&'static [&'static str]>,
+
+    /// A list of file names of images that display this ROM.
+    pub images: Option<&'static [&'static str]>,
+
+    /// A list of URLs that are relevant for this ROM.
+    pub urls: Option<&'static [&'static str]>,
+
+    /// The preferred number of cycles per frame to run the ROM at.
+    pub tickrate: Option<usize>,
+
+    /// The start address from which the ROM should be run.
+    pub start_address: Option<u16>,
+
+    /// The screen orientation, in degrees rotated clockwise from the normal position.
+    pub screen_rotation: Option<ScreenRotation>,
+
+    /// A mapping of common keys to hexadecimal key values for the CHIP-8 keypad.
+    pub keys: Option<&'static [(Keymap, u8)]>,
+
+    /// The preferred touch input mode, values as used by Octo.
+    pub touch_input_mode: Option<TouchInputMode>,
+
+    /// The preferred style of font to use by the interpreter.
+    pub font_style: Option<FontStyle>,
+
+    /// An object with hexadecimal color properties (#RRGGBB).
+    pub colors: Option<StaticColors>,
+}
+
+/// A static version of [Colors], generated at compile time.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct StaticColors {
+    /// Colors for all the states a pixel can be in.
+    pub pixels: Option<&'static [&'static str]>,
+
+    /// A color that can be used to show a visual 'beep', to accompany the sound timer.
+    pub buzzer: Option<&'static str>,
+
+    /// The color of the visual 'beep' when there is no sound.
+    pub silence: Option<&'static str>,
+}
+
+/// Lookup the program and ROM for a specific hash, without any parsing or allocation.
+pub fn lookup(hash: RomHash) -> Option<(&'static StaticProgram, &'static StaticRom)> {
+    let &(program, rom) = HASHES.get(hash.as_bytes())?;
+    let program = &PROGRAMS[program];
+
+    Some((program, &program.roms[rom].1))
+}
+
+/// Lookup the program and ROM for a specific ROM file by hashing it, without any allocation.
+pub fn lookup_rom(rom: &[u8]) -> Option<(&'static StaticProgram, &'static StaticRom)> {
+    lookup(RomHash::from_rom(rom))
+}
+
+fn strings(strs: Option<&[&str]>) -> Option<Vec<String>> {
+    strs.map(|strs| strs.iter().map(|s| s.to_string()).collect())
+}
+
+impl From<&StaticProgram> for Program {
+    fn from(program: &StaticProgram) -> Self {
+        Program {
+            title: program.title.to_owned(),
+            description: program.description.map(str::to_owned),
+            release: program.release.map(str::to_owned),
+            origin: program.origin.map(|origin| Origin {
+                origin_type: origin.origin_type,
+                reference: origin.reference.map(str::to_owned),
+            }),
+            copyright: program.copyright.map(str::to_owned),
+            license: program.license.map(str::to_owned),
+            authors: strings(program.authors),
+            images: strings(program.images),
+            urls: strings(program.urls),
+            roms: program
+                .roms
+                .iter()
+                .map(|(hash, rom)| (*hash, rom.into()))
+                .collect(),
+        }
+    }
+}
+
+impl From<&StaticRom> for Rom {
+    fn from(rom: &StaticRom) -> Self {
+        Rom {
+            file_name: rom.file_name.map(str::to_owned),
+            embedded_title: rom.embedded_title.map(str::to_owned),
+            description: rom.description.map(str::to_owned),
+            release: rom.release.map(str::to_owned),
+            platforms: rom.platforms.to_vec(),
+            quirky_platforms: rom
+                .quirky_platforms
+                .map(|platforms| platforms.iter().copied().collect()),
+            authors: strings(rom.authors),
+            images: strings(rom.images),
+            urls: strings(rom.urls),
+            tickrate: rom.tickrate,
+            start_address: rom.start_address,
+            screen_rotation: rom.screen_rotation,
+            keys: rom.keys.map(|keys| keys.iter().copied().collect()),
+            touch_input_mode: rom.touch_input_mode,
+            font_style: rom.font_style,
+            colors: rom.colors.map(|colors| Colors {
+                pixels: strings(colors.pixels),
+                buzzer: colors.buzzer.map(str::to_owned),
+                silence: colors.silence.map(str::to_owned),
+            }),
+        }
+    }
+}
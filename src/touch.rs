@@ -0,0 +1,199 @@
+//! Definitions related to on-screen touch controls for each [TouchInputMode].
+//!
+//! Regions are normalized to the screen of the device, from `0.0` to `1.0` on both axes with the
+//! origin in the top left corner. The device is assumed to be held in the orientation of the
+//! [ScreenRotation] of the ROM: landscape for [Landscape](ScreenRotation::Landscape) and
+//! [LandscapeFlipped](ScreenRotation::LandscapeFlipped), and portrait otherwise. Flipped rotations
+//! use the same layout, since the controls stay upright when the device is turned around.
+//!
+//! ```rust
+//! # use chip8_db::{input::TouchInputMode, rotation::ScreenRotation};
+//! #
+//! let regions = TouchInputMode::Seg16.layout(ScreenRotation::Landscape, None);
+//! let pressed = regions.iter().find(|region| region.rect.contains(0.1, 0.1));
+//!
+//! assert_eq!(Some(0x1), pressed.map(|region| region.key));
+//! ```
+
+use std::collections::HashMap;
+
+use crate::{
+    input::{Keymap, TouchInputMode},
+    rotation::ScreenRotation,
+};
+
+/// The hexadecimal keypad of the COSMAC VIP, by row and then by column.
+pub const KEYPAD: [[u8; 4]; 4] = [
+    [0x1, 0x2, 0x3, 0xC],
+    [0x4, 0x5, 0x6, 0xD],
+    [0x7, 0x8, 0x9, 0xE],
+    [0xA, 0x0, 0xB, 0xF],
+];
+
+/// A rectangle in normalized screen coordinates.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Rect {
+    /// The left edge, from `0.0` to `1.0`.
+    pub x: f32,
+
+    /// The top edge, from `0.0` to `1.0`.
+    pub y: f32,
+
+    /// The width, as a fraction of the width of the screen.
+    pub width: f32,
+
+    /// The height, as a fraction of the height of the screen.
+    pub height: f32,
+}
+
+impl Rect {
+    /// Create a rectangle from its top left corner and size.
+    pub const fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Whether a point in normalized screen coordinates lies inside this rectangle, including the
+    /// top and left edges. The bottom and right edges are only included where they lie on the edge
+    /// of the screen, so neighbouring cells never overlap and a touch at `1.0` still hits a cell.
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        let within = |start: f32, size: f32, point: f32| {
+            let end = start + size;
+
+            start <= point && (point < end || (end >= 1.0 && point <= 1.0))
+        };
+
+        within(self.x, self.width, x) && within(self.y, self.height, y)
+    }
+
+    /// The cell at `row` and `column` when dividing this rectangle into an even grid.
+    fn cell(&self, rows: usize, columns: usize, row: usize, column: usize) -> Rect {
+        let width = self.width / columns as f32;
+        let height = self.height / rows as f32;
+
+        Rect::new(
+            self.x + column as f32 * width,
+            self.y + row as f32 * height,
+            width,
+            height,
+        )
+    }
+}
+
+/// An area of the screen that presses a CHIP-8 key while it is touched.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TouchRegion {
+    /// The area of the screen, in normalized coordinates.
+    pub rect: Rect,
+
+    /// The CHIP-8 key to press.
+    pub key: u8,
+
+    /// Whether the region should be drawn, or is an invisible area on top of the game screen.
+    pub visible: bool,
+}
+
+/// A gesture recognized in [TouchInputMode::Swipe].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum SwipeGesture {
+    Up,
+    Down,
+    Left,
+    Right,
+
+    /// A touch that ends without moving far enough to count as a swipe.
+    Tap,
+}
+
+impl SwipeGesture {
+    /// The button this gesture presses, following Octo: swipes press the directions of player 1
+    /// and a tap presses [Keymap::P1A].
+    pub const fn button(self) -> Keymap {
+        match self {
+            SwipeGesture::Up => Keymap::P1Up,
+            SwipeGesture::Down => Keymap::P1Down,
+            SwipeGesture::Left => Keymap::P1Left,
+            SwipeGesture::Right => Keymap::P1Right,
+            SwipeGesture::Tap => Keymap::P1A,
+        }
+    }
+
+    /// The CHIP-8 key this gesture presses, given the [keys](crate::rom::Rom::keys) of a ROM.
+    /// Returns `None` if the ROM doesn't map the [button](SwipeGesture::button) of this gesture.
+    pub fn key(self, keys: Option<&HashMap<Keymap, u8>>) -> Option<u8> {
+        keys?.get(&self.button()).copied()
+    }
+}
+
+impl TouchInputMode {
+    /// The on-screen regions for this mode, for a ROM with the given rotation and
+    /// [keys](crate::rom::Rom::keys).
+    ///
+    /// - [None](TouchInputMode::None) and [Swipe](TouchInputMode::Swipe) have no regions; swipes
+    ///   are mapped with [SwipeGesture::key] instead.
+    /// - [Seg16](TouchInputMode::Seg16) and [Seg16Fill](TouchInputMode::Seg16Fill) divide the
+    ///   whole screen into the 4x4 [KEYPAD], which is only visible for the latter.
+    /// - [Gamepad](TouchInputMode::Gamepad) places a D-Pad in the bottom left and the A and B
+    ///   buttons in the bottom right, for the buttons that the ROM maps.
+    /// - [VIP](TouchInputMode::VIP) shows the [KEYPAD] next to the game screen in landscape, or
+    ///   below it in portrait.
+    pub fn layout(
+        self,
+        rotation: ScreenRotation,
+        keys: Option<&HashMap<Keymap, u8>>,
+    ) -> Vec<TouchRegion> {
+        let portrait = rotation.is_portrait();
+
+        match self {
+            TouchInputMode::None | TouchInputMode::Swipe => Vec::new(),
+            TouchInputMode::Seg16 => keypad(Rect::new(0.0, 0.0, 1.0, 1.0), false),
+            TouchInputMode::Seg16Fill => keypad(Rect::new(0.0, 0.0, 1.0, 1.0), true),
+            TouchInputMode::VIP if portrait => keypad(Rect::new(0.0, 0.5, 1.0, 0.5), true),
+            TouchInputMode::VIP => keypad(Rect::new(0.5, 0.0, 0.5, 1.0), true),
+            TouchInputMode::Gamepad => {
+                let (pad, buttons) = if portrait {
+                    (Rect::new(0.0, 0.6, 0.5, 0.4), Rect::new(0.5, 0.7, 0.5, 0.2))
+                } else {
+                    (Rect::new(0.0, 0.4, 0.3, 0.6), Rect::new(0.7, 0.6, 0.3, 0.2))
+                };
+
+                let controls = [
+                    (pad.cell(3, 3, 0, 1), Keymap::P1Up),
+                    (pad.cell(3, 3, 2, 1), Keymap::P1Down),
+                    (pad.cell(3, 3, 1, 0), Keymap::P1Left),
+                    (pad.cell(3, 3, 1, 2), Keymap::P1Right),
+                    (buttons.cell(1, 2, 0, 0), Keymap::P1B),
+                    (buttons.cell(1, 2, 0, 1), Keymap::P1A),
+                ];
+
+                controls
+                    .into_iter()
+                    .filter_map(|(rect, button)| {
+                        let key = *keys?.get(&button)?;
+
+                        Some(TouchRegion {
+                            rect,
+                            key,
+                            visible: true,
+                        })
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+fn keypad(area: Rect, visible: bool) -> Vec<TouchRegion> {
+    (0..4)
+        .flat_map(|row| (0..4).map(move |column| (row, column)))
+        .map(|(row, column)| TouchRegion {
+            rect: area.cell(4, 4, row, column),
+            key: KEYPAD[row][column],
+            visible,
+        })
+        .collect()
+}
@@ -0,0 +1,156 @@
+//! Definitions related to validating the consistency of the database.
+
+use std::{collections::HashMap, fmt::Display};
+
+use crate::{hash::RomHash, Database};
+
+/// A single consistency problem between `programs.json` and `sha1-hashes.json`.
+#[non_exhaustive]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Issue {
+    /// A hash points at a program index past the end of the list of programs.
+    DanglingIndex {
+        /// The hash with the invalid index.
+        hash: RomHash,
+
+        /// The index into the list of programs, which is out of bounds.
+        index: usize,
+    },
+
+    /// A hash points at a program that does not list that hash in its ROMs.
+    MissingRom {
+        /// The hash that has no matching ROM.
+        hash: RomHash,
+
+        /// The index of the program the hash points at.
+        index: usize,
+    },
+
+    /// A ROM listed in a program can't be reached through the hashes, either because the hash is
+    /// missing or because it points at a different program.
+    OrphanRom {
+        /// The hash of the unreachable ROM.
+        hash: RomHash,
+
+        /// The index of the program that lists the ROM.
+        index: usize,
+    },
+
+    /// The same ROM hash is listed under more than one program.
+    DuplicateHash {
+        /// The hash that is listed more than once.
+        hash: RomHash,
+
+        /// The indices of every program that lists the hash, in ascending order.
+        indices: Vec<usize>,
+    },
+
+    /// A program does not list any ROMs.
+    EmptyProgram {
+        /// The index of the program without ROMs.
+        index: usize,
+    },
+}
+
+impl Display for Issue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Issue::DanglingIndex { hash, index } => {
+                write!(
+                    f,
+                    "hash {hash} points at program {index}, which does not exist"
+                )
+            }
+            Issue::MissingRom { hash, index } => {
+                write!(
+                    f,
+                    "hash {hash} points at program {index}, which has no such ROM"
+                )
+            }
+            Issue::OrphanRom { hash, index } => {
+                write!(
+                    f,
+                    "ROM {hash} in program {index} is not reachable by its hash"
+                )
+            }
+            Issue::DuplicateHash { hash, indices } => {
+                write!(f, "ROM {hash} is listed in multiple programs: {indices:?}")
+            }
+            Issue::EmptyProgram { index } => write!(f, "program {index} has no ROMs"),
+        }
+    }
+}
+
+/// The result of [Database::validate], listing every consistency problem that was found.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ValidationReport {
+    /// Every problem found in the database, sorted so reports can be compared between runs.
+    pub issues: Vec<Issue>,
+}
+
+impl ValidationReport {
+    /// Whether the database passed validation without any issues.
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+impl Display for ValidationReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_valid() {
+            return write!(f, "no issues found");
+        }
+
+        for issue in &self.issues {
+            writeln!(f, "{issue}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Database {
+    /// Check that `programs` and `hashes` agree with each other, reporting every problem found.
+    ///
+    /// The embedded database is expected to always pass validation, but custom databases loaded at
+    /// runtime or modified after loading might not.
+    pub fn validate(&self) -> ValidationReport {
+        let mut issues = Vec::new();
+
+        for (hash, &index) in &self.hashes {
+            match self.programs.get(index) {
+                None => issues.push(Issue::DanglingIndex { hash: *hash, index }),
+                Some(program) if !program.roms.contains_key(hash) => {
+                    issues.push(Issue::MissingRom { hash: *hash, index })
+                }
+                Some(_) => {}
+            }
+        }
+
+        let mut listed_in: HashMap<RomHash, Vec<usize>> = HashMap::new();
+
+        for (index, program) in self.programs.iter().enumerate() {
+            if program.roms.is_empty() {
+                issues.push(Issue::EmptyProgram { index });
+            }
+
+            for hash in program.roms.keys() {
+                listed_in.entry(*hash).or_default().push(index);
+
+                if self.hashes.get(hash) != Some(&index) {
+                    issues.push(Issue::OrphanRom { hash: *hash, index });
+                }
+            }
+        }
+
+        for (hash, indices) in listed_in {
+            if indices.len() > 1 {
+                issues.push(Issue::DuplicateHash { hash, indices });
+            }
+        }
+
+        issues.sort();
+
+        ValidationReport { issues }
+    }
+}